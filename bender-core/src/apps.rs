@@ -0,0 +1,170 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::paths;
+use eyre::Result;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+};
+
+/// Directories that directly contain app bundles, such as system apps.
+const APP_DIRS: &[&str] = &["/Applications"];
+/// Directories that contain one container per app, each with an app bundle inside,
+/// such as App Store apps.
+const CONTAINER_DIRS: &[&str] = &["/var/containers/Bundle/Application"];
+
+/// Something that knows which apps are installed.
+pub trait AppCatalog {
+	/// Get every installed app.
+	fn apps(&self) -> Result<&[App]>;
+}
+
+// A plain list of apps is a perfectly fine app catalog,
+// which is handy for fixtures and manifests.
+impl AppCatalog for Vec<App> {
+	fn apps(&self) -> Result<&[App]> {
+		Ok(self)
+	}
+}
+
+/// Finds the installed apps by walking the app bundles on the filesystem and reading their `Info.plist`.
+/// Unlike asking `LSApplicationWorkspace`, this works under a different root, and on any platform.
+#[derive(Default)]
+pub struct BundleApps {
+	apps: OnceCell<Vec<App>>,
+}
+
+impl AppCatalog for BundleApps {
+	fn apps(&self) -> Result<&[App]> {
+		// This is initialized on first use, then reused for subsequent uses.
+		Ok(self.apps.get_or_init(|| {
+			let containers = CONTAINER_DIRS
+				.iter()
+				.flat_map(|dir| read_device_dir(Path::new(dir)));
+			let mut apps: Vec<App> = APP_DIRS
+				.iter()
+				.map(PathBuf::from)
+				.chain(containers)
+				.flat_map(|dir| read_device_dir(&dir))
+				// Only look at actual app bundles
+				.filter(|path| path.extension().and_then(OsStr::to_str) == Some("app"))
+				.filter_map(|bundle| App::from_bundle(&bundle))
+				.collect();
+			apps.sort_by(|a, b| a.name.cmp(&b.name));
+			apps
+		}))
+	}
+}
+
+/// Get the contents of a directory on the device, as paths on the device.
+/// If the directory doesn't exist, it just has no contents.
+fn read_device_dir(dir: &Path) -> Vec<PathBuf> {
+	std::fs::read_dir(paths::resolve(dir))
+		.map(|entries| {
+			entries
+				.filter_map(|entry| entry.ok())
+				.map(|entry| dir.join(entry.file_name()))
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
+/// The parts of an app's `Info.plist` that we care about.
+#[derive(Deserialize)]
+struct InfoPlist {
+	#[serde(rename = "CFBundleIdentifier")]
+	identifier: String,
+	#[serde(rename = "CFBundleDisplayName")]
+	display_name: Option<String>,
+	#[serde(rename = "CFBundleName")]
+	name: Option<String>,
+	#[serde(rename = "CFBundleExecutable")]
+	executable: Option<String>,
+	#[serde(rename = "CFBundleShortVersionString")]
+	short_version: Option<String>,
+	#[serde(rename = "CFBundleVersion")]
+	version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct App {
+	pub identifier: String,
+	pub name: String,
+	/// The full path to the app's executable on the device, if we know it.
+	#[serde(default)]
+	pub executable: Option<PathBuf>,
+	/// The app's version, if we know it.
+	#[serde(default)]
+	pub version: Option<String>,
+}
+
+impl App {
+	/// Read an app's information from it's bundle on the device,
+	/// such as `/Applications/MobileSafari.app`.
+	pub fn from_bundle(bundle: &Path) -> Option<Self> {
+		let info: InfoPlist = plist::from_file(paths::resolve(bundle.join("Info.plist"))).ok()?;
+		let identifier = info.identifier;
+		let executable = info.executable.map(|executable| bundle.join(executable));
+		// Not every app has a display name, so we fall back to the bundle name,
+		// then the executable name, and then finally the bundle ID.
+		let name = info
+			.display_name
+			.or(info.name)
+			.or_else(|| {
+				executable
+					.as_ref()
+					.and_then(|path| path.file_name())
+					.map(|name| name.to_string_lossy().to_string())
+			})
+			.unwrap_or_else(|| identifier.clone());
+		Some(Self {
+			identifier,
+			name,
+			executable,
+			version: info.short_version.or(info.version),
+		})
+	}
+}
+
+pub fn fix_app_name(catalog: &dyn AppCatalog, input: &str) -> Result<Option<String>> {
+	let normalized_input = input.trim().to_lowercase();
+	Ok(catalog
+		.apps()?
+		.iter()
+		.find(|app| {
+			let id = app.identifier.trim().to_lowercase();
+			let name = app.name.trim().to_lowercase();
+			let executable = app
+				.executable
+				.as_ref()
+				.and_then(|path| path.file_name())
+				.and_then(OsStr::to_str)
+				.map(|name| name.trim().to_lowercase());
+
+			id == normalized_input
+				|| name == normalized_input
+				|| name.replace(' ', "") == normalized_input.replace(' ', "")
+				|| executable.as_deref() == Some(normalized_input.as_str())
+		})
+		.map(|app| app.identifier.clone()))
+}
+
+/// Get the display name of an app from it's bundle ID,
+/// falling back to the bundle ID itself if we can't find the app.
+pub fn get_friendly_app_name(catalog: &dyn AppCatalog, id: &str) -> String {
+	catalog
+		.apps()
+		.ok()
+		.and_then(|apps| apps.iter().find(|app| app.identifier == id))
+		.map(|app| app.name.clone())
+		.unwrap_or_else(|| id.to_string())
+}
@@ -0,0 +1,116 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::paths;
+use eyre::Result;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+};
+
+/// Directories that launchd loads daemon plists from.
+const LAUNCH_DAEMON_DIRS: &[&str] = &[
+	"/System/Library/LaunchDaemons",
+	"/Library/LaunchDaemons",
+	"/System/Library/NanoLaunchDaemons",
+];
+
+/// Something that knows which daemons and services exist.
+pub trait ServiceCatalog {
+	/// Get every daemon, sorted by the path to it's executable.
+	fn daemons(&self) -> Result<&[Daemon]>;
+}
+
+// A plain list of daemons is a perfectly fine service catalog,
+// which is handy for fixtures and manifests.
+impl ServiceCatalog for Vec<Daemon> {
+	fn daemons(&self) -> Result<&[Daemon]> {
+		Ok(self)
+	}
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Daemon {
+	/// The launchd label of this daemon, such as `com.apple.backboardd`.
+	pub label: String,
+	/// The full path to this daemon's executable on the device.
+	pub executable: PathBuf,
+}
+
+impl Daemon {
+	/// Get the file name of this daemon's executable, which is what people usually call it.
+	pub fn name(&self) -> Option<&str> {
+		self.executable.file_name().and_then(OsStr::to_str)
+	}
+}
+
+/// Whether a daemon should be left out of the daemon list.
+/// We don't include jailbreakd, amfidebilitate, or UIKit shit.
+pub fn is_ignored_label(label: &str) -> bool {
+	label == "jailbreakd" || label == "amfidebilitate" || label.starts_with("UIKitApplication:")
+}
+
+/// Finds the daemons by parsing the launchd plists in the LaunchDaemons directories.
+/// Unlike asking launchd, this includes daemons that aren't loaded right now,
+/// and works under a different root, and on any platform.
+#[derive(Default)]
+pub struct LaunchDaemonPlists {
+	daemons: OnceCell<Vec<Daemon>>,
+}
+
+impl ServiceCatalog for LaunchDaemonPlists {
+	fn daemons(&self) -> Result<&[Daemon]> {
+		// This is initialized on first use, then reused for subsequent uses.
+		Ok(self.daemons.get_or_init(|| {
+			let mut daemons: Vec<Daemon> = LAUNCH_DAEMON_DIRS
+				.iter()
+				// If a directory doesn't exist, it just has no daemons.
+				.filter_map(|dir| std::fs::read_dir(paths::resolve(dir)).ok())
+				.flatten()
+				.filter_map(|entry| entry.ok())
+				.map(|entry| entry.path())
+				.filter(|path| path.extension().and_then(OsStr::to_str) == Some("plist"))
+				.filter_map(|path| read_launchd_plist(&path))
+				.filter(|daemon| !is_ignored_label(&daemon.label))
+				// And ensure that the executable is, in fact, a valid, existing file.
+				.filter(|daemon| paths::resolve(&daemon.executable).is_file())
+				.collect();
+			// Pre-sort the daemons.
+			daemons.sort_by(|a, b| a.executable.cmp(&b.executable));
+			daemons
+		}))
+	}
+}
+
+/// The parts of a launchd plist that we care about.
+#[derive(Deserialize)]
+struct LaunchdPlist {
+	#[serde(rename = "Label")]
+	label: String,
+	#[serde(rename = "Program")]
+	program: Option<PathBuf>,
+	#[serde(rename = "ProgramArguments", default)]
+	program_arguments: Vec<String>,
+}
+
+fn read_launchd_plist(path: &Path) -> Option<Daemon> {
+	let plist: LaunchdPlist = plist::from_file(path).ok()?;
+	// Just like launchd, `Program` wins over the first entry of `ProgramArguments`.
+	let program_arguments = plist.program_arguments;
+	let executable = plist
+		.program
+		.or_else(|| program_arguments.first().map(PathBuf::from))
+		.filter(|executable| executable.is_absolute())?;
+	Some(Daemon {
+		label: plist.label,
+		executable,
+	})
+}
@@ -0,0 +1,272 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::libhooker::{
+	serialize_mode_name, LibhookerConfig, Target, TweakConfig, TweakMode, COMPAT_LIBHOOKER,
+};
+use plist::{Dictionary, Value};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One difference between two libhooker configurations.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+	/// The target has a configuration of it's own in the new configuration, but not the old one.
+	/// Every setting of the new target is listed after this, as a change from the defaults.
+	TargetAdded { target: Target },
+	/// The target has a configuration of it's own in the old configuration, but not the new one.
+	/// Every setting of the old target is listed after this, as a change to the defaults.
+	TargetRemoved { target: Target },
+	/// `customConfig` of a target changed.
+	CustomConfig {
+		target: Target,
+		old: bool,
+		new: bool,
+	},
+	/// `enableTweaks` of a target changed.
+	EnableTweaks {
+		target: Target,
+		old: bool,
+		new: bool,
+	},
+	/// `allowDeny` of a target changed.
+	Mode {
+		target: Target,
+		#[serde(serialize_with = "serialize_mode_name")]
+		old: TweakMode,
+		#[serde(serialize_with = "serialize_mode_name")]
+		new: TweakMode,
+	},
+	/// A tweak's entry in a target's configuration was added, removed, or changed.
+	Tweak {
+		target: Target,
+		tweak: String,
+		old: Option<bool>,
+		new: Option<bool>,
+	},
+	/// The compatibility mode (`memPrefs`) of a tweak changed.
+	Compat {
+		tweak: String,
+		#[serde(serialize_with = "compat_name")]
+		old: bool,
+		#[serde(serialize_with = "compat_name")]
+		new: bool,
+	},
+	/// Whether tweaks are loaded into WebKit processes (`webProcessTweaks`) changed.
+	WebProcessTweaks { old: bool, new: bool },
+	/// A key that bender doesn't know about was added, removed, or changed.
+	/// These are in a target's configuration, or at the top level if there's no target.
+	/// Keys next to the targets themselves (in `tweakconfigs`) start with `tweakconfigs.`.
+	UnknownKey {
+		target: Option<Target>,
+		key: String,
+		old: Option<Value>,
+		new: Option<Value>,
+	},
+}
+
+impl Change {
+	/// The target this change is to, if it's to a target at all.
+	pub fn target(&self) -> Option<&Target> {
+		match self {
+			Change::TargetAdded { target }
+			| Change::TargetRemoved { target }
+			| Change::CustomConfig { target, .. }
+			| Change::EnableTweaks { target, .. }
+			| Change::Mode { target, .. }
+			| Change::Tweak { target, .. } => Some(target),
+			Change::UnknownKey { target, .. } => target.as_ref(),
+			Change::Compat { .. } | Change::WebProcessTweaks { .. } => None,
+		}
+	}
+}
+
+/// Sum up a list of changes in one line, such as "3 changes to com.apple.mobilesafari and compatibility modes".
+pub fn summarize(changes: &[Change]) -> String {
+	// Everything that was changed, in the order it was first changed.
+	let mut changed = Vec::<String>::new();
+	for change in changes {
+		let what = match (change, change.target()) {
+			(_, Some(target)) => target.key(),
+			(Change::Compat { .. }, _) => "compatibility modes".to_string(),
+			(Change::UnknownKey { .. }, _) => "unknown keys".to_string(),
+			_ => "webProcessTweaks".to_string(),
+		};
+		if !changed.contains(&what) {
+			changed.push(what);
+		}
+	}
+	let changed = match changed.split_last() {
+		None => return "no changes".to_string(),
+		Some((last, [])) => last.clone(),
+		Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+	};
+	format!(
+		"{} change{} to {}",
+		changes.len(),
+		if changes.len() == 1 { "" } else { "s" },
+		changed
+	)
+}
+
+/// Find every difference between two configurations.
+/// Targets come first (the default configuration, then executables, then apps), followed by compatibility modes,
+/// and finally any keys we don't know about.
+pub fn diff(old: &LibhookerConfig, new: &LibhookerConfig) -> Vec<Change> {
+	let mut changes = Vec::new();
+	let (old_targets, new_targets) = (&old.tweak_configs, &new.tweak_configs);
+
+	diff_target(
+		&mut changes,
+		Target::Default,
+		configured_default(&old_targets.default),
+		configured_default(&new_targets.default),
+	);
+	for (path, (old, new)) in union(&old_targets.paths, &new_targets.paths) {
+		diff_target(&mut changes, Target::Executable(path.clone()), old, new);
+	}
+	for (bundle, (old, new)) in union(&old_targets.bundles, &new_targets.bundles) {
+		diff_target(&mut changes, Target::App(bundle.clone()), old, new);
+	}
+
+	// A tweak without a compatibility mode is in libhooker's default mode.
+	for (tweak, (old, new)) in union(&old.memory_compat_prefs, &new.memory_compat_prefs) {
+		let old = old.copied().unwrap_or(COMPAT_LIBHOOKER);
+		let new = new.copied().unwrap_or(COMPAT_LIBHOOKER);
+		if old != new {
+			changes.push(Change::Compat {
+				tweak: tweak.clone(),
+				old,
+				new,
+			});
+		}
+	}
+
+	if old.web_process_tweaks != new.web_process_tweaks {
+		changes.push(Change::WebProcessTweaks {
+			old: old.web_process_tweaks,
+			new: new.web_process_tweaks,
+		});
+	}
+
+	// Nobody knows what unknown keys do, but changing them is still a change.
+	diff_unknown_keys(
+		&mut changes,
+		None,
+		"tweakconfigs.",
+		&old_targets.extra,
+		&new_targets.extra,
+	);
+	diff_unknown_keys(&mut changes, None, "", &old.extra, &new.extra);
+	changes
+}
+
+fn diff_target(
+	changes: &mut Vec<Change>,
+	target: Target,
+	old: Option<&TweakConfig>,
+	new: Option<&TweakConfig>,
+) {
+	// A target that's been added or removed is compared against the defaults,
+	// so that we can see what it was (or will be) set to.
+	let defaults = TweakConfig::default();
+	match (old, new) {
+		(None, None) => return,
+		(None, Some(_)) => changes.push(Change::TargetAdded {
+			target: target.clone(),
+		}),
+		(Some(_), None) => changes.push(Change::TargetRemoved {
+			target: target.clone(),
+		}),
+		(Some(_), Some(_)) => {}
+	}
+	let (old, new) = (old.unwrap_or(&defaults), new.unwrap_or(&defaults));
+
+	if old.custom_config != new.custom_config {
+		changes.push(Change::CustomConfig {
+			target: target.clone(),
+			old: old.custom_config,
+			new: new.custom_config,
+		});
+	}
+	if old.enable_tweaks != new.enable_tweaks {
+		changes.push(Change::EnableTweaks {
+			target: target.clone(),
+			old: old.enable_tweaks,
+			new: new.enable_tweaks,
+		});
+	}
+	if old.allow_or_deny != new.allow_or_deny {
+		changes.push(Change::Mode {
+			target: target.clone(),
+			old: old.allow_or_deny,
+			new: new.allow_or_deny,
+		});
+	}
+	for (tweak, (old, new)) in union(&old.tweak_configs, &new.tweak_configs) {
+		if old != new {
+			changes.push(Change::Tweak {
+				target: target.clone(),
+				tweak: tweak.clone(),
+				old: old.copied(),
+				new: new.copied(),
+			});
+		}
+	}
+	diff_unknown_keys(changes, Some(target), "", &old.extra, &new.extra);
+}
+
+fn diff_unknown_keys(
+	changes: &mut Vec<Change>,
+	target: Option<Target>,
+	prefix: &str,
+	old: &Dictionary,
+	new: &Dictionary,
+) {
+	let keys = old.keys().chain(new.keys()).collect::<BTreeSet<&String>>();
+	for key in keys {
+		let (old, new) = (old.get(key), new.get(key));
+		if old != new {
+			changes.push(Change::UnknownKey {
+				target: target.clone(),
+				key: format!("{}{}", prefix, key),
+				old: old.cloned(),
+				new: new.cloned(),
+			});
+		}
+	}
+}
+
+// The default configuration always exists as far as libhooker cares,
+// so it only counts as added or removed if it's gone from or to nothing at all.
+fn configured_default(config: &TweakConfig) -> Option<&TweakConfig> {
+	Some(config).filter(|config| !config.is_unset())
+}
+
+/// Pair up the values of every key in either map.
+fn union<'a, K: Ord, V>(
+	old: &'a BTreeMap<K, V>,
+	new: &'a BTreeMap<K, V>,
+) -> BTreeMap<&'a K, (Option<&'a V>, Option<&'a V>)> {
+	old.keys()
+		.chain(new.keys())
+		.collect::<BTreeSet<&K>>()
+		.into_iter()
+		.map(|key| (key, (old.get(key), new.get(key))))
+		.collect()
+}
+
+fn compat_name<S: Serializer>(compat_mode: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(if *compat_mode == COMPAT_LIBHOOKER {
+		"libhooker"
+	} else {
+		"substrate"
+	})
+}
@@ -0,0 +1,310 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	libhooker::LibhookerConfig,
+	paths,
+	tweaks::{TweakInjectDir, TweakSource},
+};
+use eyre::{eyre, Result};
+use serde::Serialize;
+use std::{
+	ffi::CString,
+	fs::OpenOptions,
+	os::unix::{ffi::OsStrExt, fs::MetadataExt},
+	path::Path,
+};
+
+/// The package ID of libhooker.
+pub const LIBHOOKER_PACKAGE: &str = "org.coolstar.libhooker";
+/// The oldest version of libhooker that bender works with.
+pub const MIN_LIBHOOKER_VERSION: &str = "1.4.0";
+/// The user (and group) ID of `mobile`, which owns the libhooker plist.
+const MOBILE: u32 = 501;
+
+/// How bad the result of a check is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+	Ok,
+	/// Something's off, but bender can still work.
+	Warning,
+	/// bender can't work (or can't work properly) until this is fixed.
+	Error,
+}
+
+/// The result of checking one of the things bender needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+	/// What was checked, such as "bootstrap".
+	pub name: &'static str,
+	pub status: Status,
+	/// What we found.
+	pub detail: String,
+	/// What to do about it, if anything's wrong.
+	pub fix: Option<String>,
+}
+
+impl Check {
+	fn ok(name: &'static str, detail: String) -> Self {
+		Self {
+			name,
+			status: Status::Ok,
+			detail,
+			fix: None,
+		}
+	}
+
+	fn problem(name: &'static str, status: Status, detail: String, fix: String) -> Self {
+		Self {
+			name,
+			status,
+			detail,
+			fix: Some(fix),
+		}
+	}
+
+	/// Turn a failed check into an error, so that other commands fail with the same explanation.
+	/// Warnings aren't errors.
+	pub fn require(self) -> Result<()> {
+		match (self.status, self.fix) {
+			(Status::Error, Some(fix)) => Err(eyre!("{}\n{}", self.detail, fix)),
+			(Status::Error, None) => Err(eyre!("{}", self.detail)),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Check everything bender needs, in order.
+pub fn run() -> Vec<Check> {
+	vec![
+		bootstrap(),
+		libhooker_package(),
+		tweak_inject(),
+		plist(),
+		plist_writable(),
+		plist_owner(),
+		launchctl(),
+	]
+}
+
+/// Check that the filesystem has a Procursus bootstrap, which is the only one libhooker supports.
+pub fn bootstrap() -> Check {
+	if paths::resolve(paths::PROCURSUS_STRAPPED).is_file() {
+		return Check::ok("bootstrap", "Procursus".to_string());
+	}
+	// Tell people what they've actually got, if we can.
+	let other = [
+		("/.bootstrapped", "Elucubratus"),
+		("/.installed_unc0ver", "unc0ver"),
+	]
+	.iter()
+	.find(|(marker, _)| paths::resolve(marker).is_file())
+	.map(|(_, name)| format!("this is a {} bootstrap, not Procursus", name))
+	.unwrap_or_else(|| format!("{} doesn't exist", paths::PROCURSUS_STRAPPED));
+	Check::problem(
+		"bootstrap",
+		Status::Error,
+		other,
+		"bender (and libhooker) need a Procursus bootstrap, such as the one Odyssey, Taurine, or odysseyra1n installs."
+			.to_string(),
+	)
+}
+
+/// Check that libhooker is installed, and new enough.
+pub fn libhooker_package() -> Check {
+	const NAME: &str = "libhooker";
+	let status = match std::fs::read_to_string(paths::resolve(paths::DPKG_STATUS)) {
+		Ok(status) => status,
+		Err(err) => {
+			return Check::problem(
+				NAME,
+				Status::Warning,
+				format!("couldn't read {}: {}", paths::DPKG_STATUS, err),
+				"Make sure dpkg is working, with `dpkg -l`.".to_string(),
+			)
+		}
+	};
+	match installed_version(&status, LIBHOOKER_PACKAGE) {
+		Some(version) if version_at_least(&version, MIN_LIBHOOKER_VERSION) => {
+			Check::ok(NAME, format!("version {}", version))
+		}
+		Some(version) => Check::problem(
+			NAME,
+			Status::Warning,
+			format!(
+				"version {} is older than {}",
+				version, MIN_LIBHOOKER_VERSION
+			),
+			"Update libhooker from your package manager.".to_string(),
+		),
+		None => Check::problem(
+			NAME,
+			Status::Error,
+			format!("{} isn't installed", LIBHOOKER_PACKAGE),
+			"Install libhooker from your package manager.".to_string(),
+		),
+	}
+}
+
+/// Check that the TweakInject directory exists, since that's where we find the installed tweaks.
+pub fn tweak_inject() -> Check {
+	const NAME: &str = "TweakInject";
+	match TweakInjectDir::new(paths::tweak_inject()).tweaks() {
+		Ok(tweaks) => Check::ok(NAME, format!("{} tweaks installed", tweaks.len())),
+		Err(_) => Check::problem(
+			NAME,
+			Status::Error,
+			format!("{} couldn't be read", paths::TWEAK_INJECT),
+			"Reinstall libhooker, which provides it, or use `--manifest` if you're not on a device."
+				.to_string(),
+		),
+	}
+}
+
+/// Check that the libhooker plist can be read and parsed, if it exists.
+pub fn plist() -> Check {
+	const NAME: &str = "plist";
+	let path = paths::libhooker_plist();
+	if !path.exists() {
+		return Check::problem(
+			NAME,
+			Status::Warning,
+			format!("{} doesn't exist yet", paths::LIBHOOKER_PLIST),
+			"Nothing's been configured, the first change will create it.".to_string(),
+		);
+	}
+	match LibhookerConfig::from_file(&path) {
+		Ok(_) => Check::ok(NAME, format!("{} is valid", paths::LIBHOOKER_PLIST)),
+		Err(err) => Check::problem(
+			NAME,
+			Status::Error,
+			format!("{:#}", err),
+			"Roll back with `bender undo`, or restore a snapshot with `bender history` and `bender restore`, which work even with a broken plist. Otherwise, delete the plist to start over."
+				.to_string(),
+		),
+	}
+}
+
+/// Check that we're allowed to change the libhooker plist.
+/// Saving writes a new file next to it and renames that over it, so it's directory has to be writable too.
+pub fn plist_writable() -> Check {
+	const NAME: &str = "plist writable";
+	let path = paths::libhooker_plist();
+	// Opening for writing, without truncating, doesn't change anything.
+	let file = if path.exists() {
+		OpenOptions::new().write(true).open(&path).map(|_| ())
+	} else {
+		Ok(())
+	};
+	let dir = path.parent().map(writable_dir).unwrap_or(Ok(()));
+	let problem = match (file, dir) {
+		(Err(err), _) => format!("{} can't be written: {}", paths::LIBHOOKER_PLIST, err),
+		(Ok(()), Err(err)) => format!(
+			"{} can't be written, so the plist can't be replaced: {}",
+			Path::new(paths::LIBHOOKER_PLIST)
+				.parent()
+				.unwrap_or_else(|| Path::new("/"))
+				.display(),
+			err
+		),
+		(Ok(()), Ok(())) => {
+			return Check::ok(NAME, "bender can change the configuration".to_string())
+		}
+	};
+	Check::problem(
+		NAME,
+		Status::Error,
+		problem,
+		"Run bender as root (with sudo), or as mobile.".to_string(),
+	)
+}
+
+/// Check whether we're allowed to create (and rename) files in a directory.
+fn writable_dir(dir: &Path) -> std::io::Result<()> {
+	let dir = CString::new(dir.as_os_str().as_bytes())?;
+	if unsafe { libc::access(dir.as_ptr(), libc::W_OK | libc::X_OK) } == 0 {
+		Ok(())
+	} else {
+		Err(std::io::Error::last_os_error())
+	}
+}
+
+/// Check that the libhooker plist belongs to mobile, like every other preference plist.
+pub fn plist_owner() -> Check {
+	const NAME: &str = "plist owner";
+	let metadata = match std::fs::metadata(paths::libhooker_plist()) {
+		Ok(metadata) => metadata,
+		// There's no owner to check if it doesn't exist.
+		Err(_) => return Check::ok(NAME, "nothing to check".to_string()),
+	};
+	if metadata.uid() == MOBILE && metadata.gid() == MOBILE {
+		return Check::ok(NAME, "owned by mobile".to_string());
+	}
+	Check::problem(
+		NAME,
+		Status::Warning,
+		format!(
+			"owned by {}:{} rather than mobile",
+			metadata.uid(),
+			metadata.gid()
+		),
+		format!(
+			"Fix it with `sudo chown mobile:mobile {}`.",
+			paths::LIBHOOKER_PLIST
+		),
+	)
+}
+
+/// Check for `launchctl`, which is the best way to find daemons.
+pub fn launchctl() -> Check {
+	const NAME: &str = "launchctl";
+	match ["/usr/bin/launchctl", "/bin/launchctl"]
+		.iter()
+		.find(|path| paths::resolve(path).is_file())
+	{
+		Some(path) => Check::ok(NAME, format!("found at {}", path)),
+		None => Check::problem(
+			NAME,
+			Status::Warning,
+			"launchctl isn't installed".to_string(),
+			"Install it from your package manager, or use `--daemons-from plists`.".to_string(),
+		),
+	}
+}
+
+/// Find the version of an installed package in the contents of dpkg's status file.
+/// Packages that were removed, but not purged, are still in there, and don't count.
+pub fn installed_version(status: &str, package: &str) -> Option<String> {
+	// Every package is a paragraph of "Key: value" lines.
+	status.split("\n\n").find_map(|paragraph| {
+		let field = |key: &str| {
+			paragraph
+				.lines()
+				.find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+				.map(str::trim)
+		};
+		if field("Package") != Some(package) || !field("Status")?.ends_with(" installed") {
+			return None;
+		}
+		field("Version").map(|version| version.to_string())
+	})
+}
+
+/// Compare the numeric parts of two versions, such as "1.6.9-1" and "1.4.0".
+pub fn version_at_least(version: &str, minimum: &str) -> bool {
+	let parts = |version: &str| {
+		version
+			.split(|c: char| !c.is_ascii_digit())
+			.take_while(|part| !part.is_empty())
+			.filter_map(|part| part.parse::<u64>().ok())
+			.collect::<Vec<u64>>()
+	};
+	parts(version) >= parts(minimum)
+}
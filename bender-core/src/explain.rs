@@ -0,0 +1,235 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	filter::{Process, TweakFilter},
+	libhooker::{serialize_mode_name, ConfigTypes, Target, TweakMode},
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Which configuration a decision was made from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+	/// The target's own custom configuration.
+	Target,
+	/// The default configuration.
+	Default,
+}
+
+/// One step in libhooker's decision of whether to load a tweak, in the order libhooker makes them.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+	/// The tweak has no filter plist, so we can't tell which processes it's injected into.
+	NoFilter,
+	/// Whether or not the tweak's filter includes the target.
+	Filter { filter: TweakFilter, matches: bool },
+	/// The target has no configuration of it's own, so the default configuration applies.
+	NoTargetConfig,
+	/// Whether tweak loading is enabled at all (`enableTweaks`).
+	EnableTweaks { scope: Scope, enabled: bool },
+	/// Whether the target's custom configuration is used (`customConfig`),
+	/// rather than the default configuration.
+	CustomConfig { enabled: bool },
+	/// The allowlist/denylist mode of the configuration in use (`allowDeny`).
+	Mode {
+		scope: Scope,
+		#[serde(serialize_with = "serialize_mode_name")]
+		mode: TweakMode,
+	},
+	/// The tweak has an entry in the configuration in use.
+	Entry { scope: Scope, value: bool },
+	/// The tweak has no entry in the configuration in use, so libhooker reads it as false.
+	MissingEntry { scope: Scope },
+}
+
+/// Why a tweak will or won't load into a target.
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+	/// The file name of the tweak.
+	pub tweak: String,
+	/// Whether the tweak will load.
+	pub loads: bool,
+	/// Every step that went into that decision.
+	pub steps: Vec<Step>,
+}
+
+/// Explain whether a tweak will load into a target, and why.
+/// If the target is a process, `process` should be it, so that the tweak's filter can be checked.
+pub fn explain(
+	config: &ConfigTypes,
+	target: &Target,
+	process: Option<&Process>,
+	tweak: &str,
+	filter: Option<TweakFilter>,
+) -> Explanation {
+	let mut steps = Vec::new();
+	let decide = |steps: Vec<Step>, loads: bool| Explanation {
+		tweak: tweak.to_string(),
+		loads,
+		steps,
+	};
+
+	// First, libhooker only ever looks at tweaks whose filter matches the process.
+	if let Some(process) = process {
+		match filter {
+			Some(filter) => {
+				let matches = filter.matches(process);
+				steps.push(Step::Filter { filter, matches });
+				if !matches {
+					return decide(steps, false);
+				}
+			}
+			None => steps.push(Step::NoFilter),
+		}
+	}
+
+	// Next, figure out which configuration applies to this target.
+	let mut scope = Scope::Default;
+	if !matches!(target, Target::Default) {
+		match target.find_config(config) {
+			Some(target_config) => {
+				steps.push(Step::EnableTweaks {
+					scope: Scope::Target,
+					enabled: target_config.enable_tweaks,
+				});
+				if !target_config.enable_tweaks {
+					return decide(steps, false);
+				}
+				steps.push(Step::CustomConfig {
+					enabled: target_config.custom_config,
+				});
+				if target_config.custom_config {
+					scope = Scope::Target;
+				}
+			}
+			None => steps.push(Step::NoTargetConfig),
+		}
+	}
+	let tweak_config = match scope {
+		Scope::Target => target.find_config(config).unwrap_or(&config.default),
+		Scope::Default => {
+			// The target's own enableTweaks has already been checked,
+			// but the default configuration can still turn tweaks off everywhere.
+			steps.push(Step::EnableTweaks {
+				scope,
+				enabled: config.default.enable_tweaks,
+			});
+			if !config.default.enable_tweaks {
+				return decide(steps, false);
+			}
+			&config.default
+		}
+	};
+
+	// And finally, the tweak's own entry, interpreted through the allow/deny mode.
+	steps.push(Step::Mode {
+		scope,
+		mode: tweak_config.allow_or_deny,
+	});
+	steps.push(match tweak_config.tweak_entry(tweak) {
+		Some(value) => Step::Entry { scope, value },
+		None => Step::MissingEntry { scope },
+	});
+	let loads = tweak_config
+		.allow_or_deny
+		.check(tweak_config.tweak_value(tweak));
+	decide(steps, loads)
+}
+
+/// Where the entry libhooker uses for a tweak comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+	/// The entry is in the target's own configuration.
+	Explicit,
+	/// The target falls back to the default configuration, and the entry is in that.
+	Inherited,
+	/// There's no entry at all, so libhooker reads it as false.
+	Unset,
+}
+
+/// How libhooker sees one tweak for a target, once the default configuration has been merged in.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveTweak {
+	/// The file name of the tweak.
+	pub tweak: String,
+	/// The entry libhooker uses, if there is one.
+	pub value: Option<bool>,
+	pub origin: Origin,
+	/// Whether the tweak will load, ignoring it's filter.
+	pub loads: bool,
+}
+
+/// A target's configuration as libhooker actually uses it,
+/// rather than what happens to be in the target's own entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct Effective {
+	/// Which configuration the tweak entries and mode come from.
+	pub scope: Scope,
+	/// Whether any tweaks are loaded at all.
+	pub enable_tweaks: bool,
+	#[serde(serialize_with = "serialize_mode_name")]
+	pub mode: TweakMode,
+	/// Every tweak that's installed or has an entry, sorted by name.
+	pub tweaks: Vec<EffectiveTweak>,
+}
+
+/// Work out a target's effective configuration, the same way [explain] decides each tweak,
+/// covering every installed tweak as well as every tweak with an entry.
+pub fn effective(config: &ConfigTypes, target: &Target, installed_tweaks: &[String]) -> Effective {
+	let own_config = match target {
+		Target::Default => None,
+		target => target.find_config(config),
+	};
+	let scope = match own_config {
+		Some(own_config) if own_config.custom_config => Scope::Target,
+		_ => Scope::Default,
+	};
+	let tweak_config = match (scope, own_config) {
+		(Scope::Target, Some(own_config)) => own_config,
+		_ => &config.default,
+	};
+	// The target's own enableTweaks always counts, but the default's only counts if it's in use.
+	let enable_tweaks = own_config
+		.map(|own_config| own_config.enable_tweaks)
+		.unwrap_or(true)
+		&& (scope == Scope::Target || config.default.enable_tweaks);
+
+	let tweaks = installed_tweaks
+		.iter()
+		.chain(tweak_config.tweak_configs.keys())
+		.collect::<BTreeSet<&String>>()
+		.into_iter()
+		.map(|tweak| {
+			let value = tweak_config.tweak_entry(tweak);
+			let origin = match value {
+				None => Origin::Unset,
+				Some(_) if scope == Scope::Default && !matches!(target, Target::Default) => {
+					Origin::Inherited
+				}
+				Some(_) => Origin::Explicit,
+			};
+			EffectiveTweak {
+				tweak: tweak.clone(),
+				value,
+				origin,
+				loads: explain(config, target, None, tweak, None).loads,
+			}
+		})
+		.collect();
+	Effective {
+		scope,
+		enable_tweaks,
+		mode: tweak_config.allow_or_deny,
+		tweaks,
+	}
+}
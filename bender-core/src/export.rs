@@ -0,0 +1,195 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	apps::AppCatalog,
+	libhooker::{LibhookerConfig, TweakConfig},
+	tweaks::TweakSource,
+};
+use eyre::{bail, eyre, Result, WrapErr};
+use plist::Value;
+use std::{collections::BTreeSet, ffi::OsStr, path::Path, str::FromStr};
+
+/// A text format that a libhooker configuration can be exported to, or imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	Json,
+	Yaml,
+	Toml,
+	XmlPlist,
+}
+
+impl FromStr for Format {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().to_lowercase().as_str() {
+			"json" => Ok(Self::Json),
+			"yaml" | "yml" => Ok(Self::Yaml),
+			"toml" => Ok(Self::Toml),
+			"xml-plist" | "plist" | "xml" => Ok(Self::XmlPlist),
+			_ => Err("expected json, yaml, toml, or xml-plist".to_string()),
+		}
+	}
+}
+
+impl Format {
+	/// Guess the format of a file from it's extension.
+	pub fn from_path(path: &Path) -> Option<Self> {
+		match path.extension().and_then(OsStr::to_str)? {
+			"json" => Some(Self::Json),
+			"yaml" | "yml" => Some(Self::Yaml),
+			"toml" => Some(Self::Toml),
+			"plist" | "xml" => Some(Self::XmlPlist),
+			_ => None,
+		}
+	}
+
+	/// The name of this format, as it's passed to `--format`.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Json => "json",
+			Self::Yaml => "yaml",
+			Self::Toml => "toml",
+			Self::XmlPlist => "xml-plist",
+		}
+	}
+}
+
+/// Write a configuration out in a human-readable format.
+/// The keys are exactly the same as in libhooker's plist.
+pub fn export(config: &LibhookerConfig, format: Format) -> Result<String> {
+	// Only plists can hold data and dates. Anything else would write them down as something else,
+	// and importing that would quietly change keys we don't even know the meaning of.
+	if format != Format::XmlPlist {
+		if let Some(key) = unrepresentable_key(config) {
+			bail!(
+				"{} holds data or a date, which {} can't hold, export as xml-plist instead",
+				key,
+				format.name()
+			);
+		}
+	}
+	Ok(match format {
+		Format::Json => serde_json::to_string_pretty(config)?,
+		Format::Yaml => serde_yaml::to_string(config)?,
+		// TOML needs every plain value to come before any tables,
+		// and going through a toml::Value sorts that out for us.
+		Format::Toml => toml::to_string_pretty(&toml::Value::try_from(config)?)?,
+		Format::XmlPlist => {
+			let mut xml = Vec::new();
+			plist::to_writer_xml(&mut xml, config)?;
+			String::from_utf8(xml)?
+		}
+	})
+}
+
+/// Find the first unknown key that holds something only a plist can hold, if there is one.
+fn unrepresentable_key(config: &LibhookerConfig) -> Option<String> {
+	let configs = &config.tweak_configs;
+	let targets = std::iter::once(("tweakconfigs.default".to_string(), &configs.default))
+		.chain(configs.paths.iter().map(|(path, tweak_config)| {
+			(
+				format!("tweakconfigs.paths.{}", path.display()),
+				tweak_config,
+			)
+		}))
+		.chain(configs.bundles.iter().map(|(bundle, tweak_config)| {
+			(format!("tweakconfigs.bundles.{}", bundle), tweak_config)
+		}))
+		.map(|(prefix, tweak_config)| (format!("{}.", prefix), &tweak_config.extra));
+	std::iter::once((String::new(), &config.extra))
+		.chain(std::iter::once((
+			"tweakconfigs.".to_string(),
+			&configs.extra,
+		)))
+		.chain(targets)
+		.find_map(|(prefix, extra)| {
+			extra
+				.iter()
+				.find(|(_, value)| !is_representable(value))
+				.map(|(key, _)| format!("{}{}", prefix, key))
+		})
+}
+
+/// Whether a value can be written as JSON, YAML, or TOML, and read back exactly the same.
+fn is_representable(value: &Value) -> bool {
+	match value {
+		Value::Array(values) => values.iter().all(is_representable),
+		Value::Dictionary(dict) => dict.values().all(is_representable),
+		Value::Boolean(_) | Value::Integer(_) | Value::Real(_) | Value::String(_) => true,
+		_ => false,
+	}
+}
+
+/// Read a configuration that was exported with [export], or any libhooker plist.
+/// The format is guessed from the file's extension.
+pub fn import<P: AsRef<Path>>(path: P) -> Result<LibhookerConfig> {
+	let path = path.as_ref();
+	let format = match Format::from_path(path) {
+		Some(format) => format,
+		None => bail!(
+			"{} should end in .json, .yaml, .yml, .toml, or .plist",
+			path.display()
+		),
+	};
+	if format == Format::XmlPlist {
+		// This also handles binary plists, such as one copied straight off of a device.
+		return LibhookerConfig::from_file(path);
+	}
+	let contents = std::fs::read_to_string(path)
+		.wrap_err_with(|| format!("failed to read {}", path.display()))?;
+	match format {
+		Format::Json => serde_json::from_str(&contents).map_err(|err| eyre!(err)),
+		Format::Yaml => serde_yaml::from_str(&contents).map_err(|err| eyre!(err)),
+		_ => toml::from_str(&contents).map_err(|err| eyre!(err)),
+	}
+	.wrap_err_with(|| format!("failed to parse {}", path.display()))
+}
+
+/// Tweaks and apps that a configuration mentions, but that aren't installed.
+#[derive(Debug, Clone, Default)]
+pub struct Unknown {
+	pub tweaks: BTreeSet<String>,
+	pub bundles: BTreeSet<String>,
+}
+
+impl Unknown {
+	/// Find every tweak and app in a configuration that isn't installed.
+	pub fn find(
+		config: &LibhookerConfig,
+		tweaks: &dyn TweakSource,
+		apps: &dyn AppCatalog,
+	) -> Result<Self> {
+		let installed_tweaks = tweaks.tweaks()?;
+		let installed_apps = apps.apps()?;
+		let configs = &config.tweak_configs;
+		let mentioned_tweaks = std::iter::once(&configs.default)
+			.chain(configs.paths.values())
+			.chain(configs.bundles.values())
+			.flat_map(|tweak_config: &TweakConfig| tweak_config.tweak_configs.keys())
+			.chain(config.memory_compat_prefs.keys());
+		Ok(Self {
+			tweaks: mentioned_tweaks
+				.filter(|tweak| !installed_tweaks.contains(tweak))
+				.cloned()
+				.collect(),
+			bundles: configs
+				.bundles
+				.keys()
+				.filter(|bundle| !installed_apps.iter().any(|app| app.identifier == **bundle))
+				.cloned()
+				.collect(),
+		})
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.tweaks.is_empty() && self.bundles.is_empty()
+	}
+}
@@ -0,0 +1,213 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	apps::AppCatalog,
+	libhooker::{Target, SPRINGBOARD},
+};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::{ffi::OsStr, fmt::Display, path::Path};
+
+/// Bundles that are loaded into every process, so a filter on them matches everything.
+const EVERYWHERE_BUNDLES: &[&str] = &["com.apple.foundation", "com.apple.corefoundation"];
+/// Bundles that are loaded into every process with a UI, which means all apps and SpringBoard.
+const UI_BUNDLES: &[&str] = &["com.apple.uikit"];
+
+/// The `Filter` of a tweak's plist, which decides which processes libhooker injects the tweak into.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TweakFilter {
+	/// Inject into processes that have loaded any of these bundles.
+	pub bundles: Vec<String>,
+	/// Inject into processes with any of these executable names.
+	pub executables: Vec<String>,
+	/// Inject into processes that have any of these Objective-C classes.
+	pub classes: Vec<String>,
+	/// Only inject on these CoreFoundation versions, as a minimum and (optionally) a maximum.
+	pub core_foundation_version: Vec<f64>,
+	/// If true, the tweak is injected if *any* of bundles, executables, or classes match,
+	/// rather than *all* of them.
+	pub any: bool,
+}
+
+// The plist as it's actually written on disk.
+#[derive(Deserialize)]
+struct FilterPlist {
+	#[serde(rename = "Filter", default)]
+	filter: RawFilter,
+}
+
+#[derive(Deserialize, Default)]
+struct RawFilter {
+	#[serde(rename = "Bundles", default)]
+	bundles: Vec<String>,
+	#[serde(rename = "Executables", default)]
+	executables: Vec<String>,
+	#[serde(rename = "Classes", default)]
+	classes: Vec<String>,
+	// Old-style plists don't have numbers, just strings, so we have to be flexible here.
+	#[serde(rename = "CoreFoundationVersion", default)]
+	core_foundation_version: Vec<plist::Value>,
+	#[serde(rename = "Mode")]
+	mode: Option<String>,
+}
+
+impl TweakFilter {
+	/// Read the filter plist of a tweak, such as `/usr/lib/TweakInject/Example.plist`.
+	/// Returns `None` if there's no plist, or it couldn't be read.
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Option<Self> {
+		let plist: FilterPlist = plist::from_file(path).ok()?;
+		let raw = plist.filter;
+		Some(Self {
+			bundles: raw.bundles,
+			executables: raw.executables,
+			classes: raw.classes,
+			core_foundation_version: raw
+				.core_foundation_version
+				.iter()
+				.filter_map(|version| match version {
+					plist::Value::Real(version) => Some(*version),
+					plist::Value::Integer(version) => version.as_signed().map(|x| x as f64),
+					plist::Value::String(version) => version.trim().parse().ok(),
+					_ => None,
+				})
+				.collect(),
+			any: raw
+				.mode
+				.map(|mode| mode.eq_ignore_ascii_case("any"))
+				.unwrap_or(false),
+		})
+	}
+
+	/// Whether this filter doesn't restrict the tweak to any particular processes.
+	pub fn is_everywhere(&self) -> bool {
+		self.bundles.is_empty() && self.executables.is_empty() && self.classes.is_empty()
+	}
+
+	/// Check whether this filter would inject the tweak into the given process.
+	///
+	/// This is a best guess: we can't see which classes a process has without running it,
+	/// so a class filter is assumed to match, and we can't know the CoreFoundation version
+	/// of the device, so that's ignored.
+	pub fn matches(&self, process: &Process) -> bool {
+		if self.is_everywhere() {
+			return true;
+		}
+		let mut checks = Vec::with_capacity(3);
+		if !self.bundles.is_empty() {
+			checks.push(self.bundles.iter().any(|bundle| process.has_bundle(bundle)));
+		}
+		if !self.executables.is_empty() {
+			checks.push(self.executables.iter().any(|executable| {
+				process
+					.executable
+					.as_deref()
+					.map(|name| name.eq_ignore_ascii_case(executable))
+					.unwrap_or(false)
+			}));
+		}
+		if !self.classes.is_empty() {
+			checks.push(true);
+		}
+		if self.any {
+			checks.into_iter().any(|check| check)
+		} else {
+			checks.into_iter().all(|check| check)
+		}
+	}
+}
+
+impl Display for TweakFilter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.is_everywhere() {
+			return write!(f, "everything");
+		}
+		let parts = [
+			("bundles", &self.bundles),
+			("executables", &self.executables),
+			("classes", &self.classes),
+		]
+		.iter()
+		.filter(|(_, list)| !list.is_empty())
+		.map(|(kind, list)| format!("{} {}", kind, list.join(", ")))
+		.collect::<Vec<String>>();
+		write!(
+			f,
+			"{}",
+			parts.join(if self.any { ", or " } else { ", and " })
+		)
+	}
+}
+
+/// What we know about a process, for the purposes of matching it against tweak filters.
+#[derive(Debug, Clone, Default)]
+pub struct Process {
+	/// The bundle ID of the process, if it's an app (or SpringBoard).
+	pub bundle_id: Option<String>,
+	/// The file name of the process's executable.
+	pub executable: Option<String>,
+	/// Whether this process has a UI, and therefore loads UIKit.
+	pub has_ui: bool,
+}
+
+impl Process {
+	/// Figure out which process a target refers to.
+	/// Returns `None` for the default configuration, which isn't any particular process.
+	pub fn for_target(target: &Target, apps: &dyn AppCatalog) -> Result<Option<Self>> {
+		Ok(match target {
+			Target::Default => None,
+			Target::App(bundle_id) => {
+				let executable = apps
+					.apps()?
+					.iter()
+					.find(|app| app.identifier == *bundle_id)
+					.and_then(|app| app.executable.as_deref())
+					.and_then(file_name);
+				Some(Self {
+					bundle_id: Some(bundle_id.clone()),
+					executable,
+					has_ui: true,
+				})
+			}
+			Target::Executable(path) => {
+				// If this executable belongs to an app, then it's that app.
+				let bundle_id = if path == Path::new(SPRINGBOARD) {
+					Some("com.apple.springboard".to_string())
+				} else {
+					apps.apps()?
+						.iter()
+						.find(|app| app.executable.as_deref() == Some(path.as_path()))
+						.map(|app| app.identifier.clone())
+				};
+				Some(Self {
+					has_ui: bundle_id.is_some(),
+					bundle_id,
+					executable: file_name(path),
+				})
+			}
+		})
+	}
+
+	fn has_bundle(&self, bundle: &str) -> bool {
+		let bundle = bundle.trim().to_lowercase();
+		EVERYWHERE_BUNDLES.contains(&bundle.as_str())
+			|| (self.has_ui && UI_BUNDLES.contains(&bundle.as_str()))
+			|| self
+				.bundle_id
+				.as_ref()
+				.map(|id| id.to_lowercase() == bundle)
+				.unwrap_or(false)
+	}
+}
+
+fn file_name(path: &Path) -> Option<String> {
+	path.file_name()
+		.and_then(OsStr::to_str)
+		.map(|name| name.to_string())
+}
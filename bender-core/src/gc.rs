@@ -0,0 +1,57 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::libhooker::{LibhookerConfig, TweakConfig, COMPAT_LIBHOOKER};
+
+/// Remove everything from a configuration that doesn't change what libhooker does.
+///
+/// - Tweak entries set "off", since libhooker reads a missing entry as "off" anyways.
+/// - Targets that end up behaving exactly like the default configuration.
+/// - Compatibility modes that are libhooker's default mode.
+///
+/// Entries of targets with custom configuration off are only compacted, not removed,
+/// since they come back into play as soon as custom configuration is turned back on.
+/// The default configuration is left out of the plist when there's nothing left in it.
+pub fn compact(config: &mut LibhookerConfig) {
+	let configs = &mut config.tweak_configs;
+	compact_target(&mut configs.default);
+	let default = &configs.default;
+	configs.paths.retain(|_, tweak_config| {
+		compact_target(tweak_config);
+		!is_redundant(tweak_config, default)
+	});
+	configs.bundles.retain(|_, tweak_config| {
+		compact_target(tweak_config);
+		!is_redundant(tweak_config, default)
+	});
+	config
+		.memory_compat_prefs
+		.retain(|_, compat_mode| *compat_mode != COMPAT_LIBHOOKER);
+}
+
+fn compact_target(tweak_config: &mut TweakConfig) {
+	tweak_config.tweak_configs.retain(|_, value| *value);
+}
+
+/// Whether a (compacted) target makes exactly the same decisions as having no configuration at all.
+pub fn is_redundant(tweak_config: &TweakConfig, default: &TweakConfig) -> bool {
+	// We don't know what unknown keys do, so we can't say they do nothing.
+	if !tweak_config.extra.is_empty() || !tweak_config.enable_tweaks {
+		return false;
+	}
+	if !tweak_config.custom_config {
+		// The default configuration is used either way, but the entries are still worth keeping.
+		return tweak_config.tweak_configs.is_empty();
+	}
+	// A custom configuration doesn't care whether the default configuration turns tweak loading off,
+	// so it's only the same if the default configuration has it on.
+	default.enable_tweaks
+		&& tweak_config.allow_or_deny == default.allow_or_deny
+		&& tweak_config.tweak_configs == default.tweak_configs
+}
@@ -0,0 +1,195 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::libhooker::LibhookerConfig;
+use eyre::{Result, WrapErr};
+use std::{
+	ffi::OsStr,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How many snapshots are kept before the oldest ones are deleted.
+pub const HISTORY_LIMIT: usize = 50;
+
+/// Snapshots of the libhooker configuration, taken before every change bender makes to it.
+///
+/// Every snapshot is two files: `<id>.plist`, which is an exact copy of the configuration plist,
+/// and `<id>.txt`, which is a one-line summary of the change that was about to be made.
+/// IDs are UTC timestamps like `20210418T120000Z`, so they sort oldest to newest.
+#[derive(Debug, Clone)]
+pub struct History {
+	dir: PathBuf,
+}
+
+/// A single snapshot of the configuration.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+	/// The ID of this snapshot, which is when it was taken.
+	pub id: String,
+	/// What was changed right after this snapshot was taken.
+	pub summary: String,
+	path: PathBuf,
+}
+
+impl History {
+	pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	/// Save a snapshot of the raw configuration plist, and then delete any snapshots past [HISTORY_LIMIT].
+	pub fn record(&self, plist: &[u8], summary: &str) -> Result<Snapshot> {
+		std::fs::create_dir_all(&self.dir)
+			.wrap_err_with(|| format!("failed to create {}", self.dir.display()))?;
+
+		// Two snapshots in the same second get a number on the end, so they don't clobber each other.
+		// This is rounded to the second, since that's all the precision we need.
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|now| Duration::from_secs(now.as_secs()))
+			.unwrap_or_default();
+		let timestamp = plist::Date::from(UNIX_EPOCH + now)
+			.to_xml_format()
+			.replace(&['-', ':'][..], "");
+		// The number goes after the newest snapshot from the same second, rather than filling a gap,
+		// since the oldest ones might have been pruned already, and a new snapshot has to sort after all of them.
+		let newest = self
+			.snapshots()?
+			.into_iter()
+			.find(|snapshot| snapshot.id.starts_with(&timestamp));
+		let id = match newest {
+			None => timestamp,
+			Some(newest) => {
+				let n = newest
+					.id
+					.strip_prefix(&format!("{}-", timestamp))
+					.and_then(|n| n.parse::<u32>().ok())
+					.unwrap_or(0);
+				// The number is padded, so that -10 doesn't sort before -2.
+				format!("{}-{:03}", timestamp, n + 1)
+			}
+		};
+
+		let snapshot = Snapshot {
+			path: self.plist_path(&id),
+			id,
+			summary: summary.lines().next().unwrap_or_default().to_string(),
+		};
+		std::fs::write(&snapshot.path, plist)
+			.wrap_err_with(|| format!("failed to write {}", snapshot.path.display()))?;
+		std::fs::write(snapshot.summary_path(), &snapshot.summary)
+			.wrap_err_with(|| format!("failed to write {}", snapshot.summary_path().display()))?;
+
+		for old in self.snapshots()?.into_iter().skip(HISTORY_LIMIT) {
+			old.remove()?;
+		}
+		Ok(snapshot)
+	}
+
+	/// Get every snapshot, newest first.
+	pub fn snapshots(&self) -> Result<Vec<Snapshot>> {
+		// No history directory just means nothing's been changed yet.
+		let entries = match std::fs::read_dir(&self.dir) {
+			Ok(entries) => entries,
+			Err(_) => return Ok(Vec::new()),
+		};
+		let mut snapshots = entries
+			.filter_map(|entry| entry.ok())
+			.map(|entry| entry.path())
+			.filter(|path| path.extension().and_then(OsStr::to_str) == Some("plist"))
+			.filter_map(|path| {
+				let id = path.file_stem()?.to_str()?.to_string();
+				let summary =
+					std::fs::read_to_string(path.with_extension("txt")).unwrap_or_default();
+				Some(Snapshot { id, summary, path })
+			})
+			.collect::<Vec<Snapshot>>();
+		snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+		Ok(snapshots)
+	}
+
+	/// Find a snapshot by it's ID, or by the start of it's ID, as long as that's not ambiguous.
+	pub fn find(&self, id: &str) -> Result<Option<Snapshot>> {
+		let id = id.trim();
+		let mut matching = self
+			.snapshots()?
+			.into_iter()
+			.filter(|snapshot| snapshot.id.starts_with(id))
+			.collect::<Vec<Snapshot>>();
+		Ok(
+			match matching.iter().position(|snapshot| snapshot.id == id) {
+				Some(exact) => Some(matching.swap_remove(exact)),
+				None if matching.len() == 1 => matching.pop(),
+				None => None,
+			},
+		)
+	}
+
+	/// Get the most recent snapshot, if there is one.
+	pub fn latest(&self) -> Result<Option<Snapshot>> {
+		Ok(self.snapshots()?.into_iter().next())
+	}
+
+	fn plist_path(&self, id: &str) -> PathBuf {
+		self.dir.join(format!("{}.plist", id))
+	}
+}
+
+impl Snapshot {
+	/// When this snapshot was taken, in a more human-friendly form than the ID.
+	pub fn date(&self) -> String {
+		let id = self.id.as_str();
+		match (
+			id.get(0..4),
+			id.get(4..6),
+			id.get(6..8),
+			id.get(9..11),
+			id.get(11..13),
+			id.get(13..15),
+		) {
+			(Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) => {
+				format!(
+					"{}-{}-{} {}:{}:{} UTC",
+					year, month, day, hour, minute, second
+				)
+			}
+			_ => id.to_string(),
+		}
+	}
+
+	/// The path to the copy of the configuration plist.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Read the configuration in this snapshot.
+	pub fn load(&self) -> Result<LibhookerConfig> {
+		LibhookerConfig::from_bytes(&self.bytes()?)
+			.wrap_err_with(|| format!("failed to read {}", self.path.display()))
+	}
+
+	/// Read the raw bytes of the plist in this snapshot.
+	pub fn bytes(&self) -> Result<Vec<u8>> {
+		std::fs::read(&self.path)
+			.wrap_err_with(|| format!("failed to read {}", self.path.display()))
+	}
+
+	/// Delete this snapshot.
+	pub fn remove(self) -> Result<()> {
+		std::fs::remove_file(&self.path)
+			.wrap_err_with(|| format!("failed to delete {}", self.path.display()))?;
+		// An old snapshot without a summary is fine.
+		let _ = std::fs::remove_file(self.summary_path());
+		Ok(())
+	}
+
+	fn summary_path(&self) -> PathBuf {
+		self.path.with_extension("txt")
+	}
+}
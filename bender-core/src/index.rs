@@ -0,0 +1,80 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	explain::explain,
+	libhooker::{serialize_mode_name, ConfigTypes, Target, TweakConfig, TweakMode},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// One target's entry for a tweak.
+#[derive(Debug, Clone, Serialize)]
+pub struct TweakEntry {
+	pub target: Target,
+	/// The value of the entry itself.
+	pub value: bool,
+	/// The mode of the target's configuration, which decides what the value means.
+	#[serde(serialize_with = "serialize_mode_name")]
+	pub mode: TweakMode,
+	/// Whether the entry allows the tweak, going by the mode.
+	pub allows: bool,
+	/// Whether libhooker ignores the entry, because the target's custom configuration is off.
+	pub ignored: bool,
+	/// Whether the tweak actually loads into the target, ignoring it's filter.
+	pub loads: bool,
+}
+
+/// Every target each tweak has an entry in, so that we can go from a tweak to it's targets
+/// rather than the other way around.
+#[derive(Debug, Clone, Default)]
+pub struct TweakIndex {
+	entries: BTreeMap<String, Vec<TweakEntry>>,
+}
+
+impl TweakIndex {
+	/// Index every entry in the configuration.
+	/// The default configuration comes first, then executables, then apps.
+	pub fn build(config: &ConfigTypes) -> Self {
+		let mut index = Self::default();
+		index.add(config, Target::Default, &config.default);
+		for (path, tweak_config) in &config.paths {
+			index.add(config, Target::Executable(path.clone()), tweak_config);
+		}
+		for (bundle, tweak_config) in &config.bundles {
+			index.add(config, Target::App(bundle.clone()), tweak_config);
+		}
+		index
+	}
+
+	fn add(&mut self, config: &ConfigTypes, target: Target, tweak_config: &TweakConfig) {
+		for (tweak, value) in &tweak_config.tweak_configs {
+			let mode = tweak_config.allow_or_deny;
+			let entry = TweakEntry {
+				value: *value,
+				mode,
+				allows: mode.check(*value),
+				ignored: !matches!(target, Target::Default) && !tweak_config.custom_config,
+				loads: explain(config, &target, None, tweak, None).loads,
+				target: target.clone(),
+			};
+			self.entries.entry(tweak.clone()).or_default().push(entry);
+		}
+	}
+
+	/// Every entry for a tweak, by it's file name.
+	pub fn get(&self, tweak: &str) -> &[TweakEntry] {
+		self.entries.get(tweak).map(Vec::as_slice).unwrap_or(&[])
+	}
+
+	/// Every tweak that has at least one entry, sorted by name.
+	pub fn tweaks(&self) -> impl Iterator<Item = &String> {
+		self.entries.keys()
+	}
+}
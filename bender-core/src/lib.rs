@@ -0,0 +1,31 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+//! The platform-neutral half of bender: the model of libhooker's configuration plist,
+//! and everything needed to read, edit, and write it.
+//! Nothing in here depends on Apple frameworks, so it can be used anywhere.
+
+pub mod apps;
+pub mod daemons;
+pub mod diff;
+pub mod doctor;
+pub mod explain;
+pub mod export;
+pub mod filter;
+pub mod gc;
+pub mod history;
+pub mod index;
+pub mod libhooker;
+pub mod lint;
+pub mod manifest;
+pub mod paths;
+pub mod profiles;
+pub mod spec;
+pub mod store;
+pub mod tweaks;
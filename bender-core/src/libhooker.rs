@@ -0,0 +1,548 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	apps::{fix_app_name, get_friendly_app_name, AppCatalog},
+	daemons::ServiceCatalog,
+	paths,
+};
+use colorful::Colorful;
+use eyre::{Result, WrapErr};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::{
+	collections::BTreeMap,
+	ffi::OsStr,
+	fmt::Display,
+	io::Cursor,
+	path::{Path, PathBuf},
+};
+
+// Note: we use BTreeMap over HashMap, because BTreeMaps are sorted by their keys,
+// and we don't want to randomly shift the order of everything whenever we save the config.
+
+/// The path to SpringBoard's executable.
+pub const SPRINGBOARD: &str = "/System/Library/CoreServices/SpringBoard.app/SpringBoard";
+
+pub const COMPAT_LIBHOOKER: bool = true;
+pub const COMPAT_SUBSTRATE: bool = false;
+
+const fn default_true() -> bool {
+	true
+}
+
+const fn default_false() -> bool {
+	false
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr)]
+pub enum TweakMode {
+	Allow = 0,
+	Deny = 1,
+}
+
+impl TweakMode {
+	pub fn check(&self, val: bool) -> bool {
+		let mode = *self as u8 != 0;
+		!((mode && val) || !(mode || val))
+	}
+
+	/// The name of this mode, as it's written in spec files and machine-readable output.
+	pub fn name(&self) -> &'static str {
+		match self {
+			TweakMode::Allow => "allow",
+			TweakMode::Deny => "deny",
+		}
+	}
+}
+
+/// Serialize a tweak mode by it's name, rather than the number libhooker uses.
+pub fn serialize_mode_name<S: Serializer>(
+	mode: &TweakMode,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(mode.name())
+}
+
+impl Display for TweakMode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			// fyi: this isn't some statement about how whitelist/blacklist is bad or whatever,
+			// i'm just using the same terminology that libhooker does, as it uses the key "allowOrDeny" in the plist.
+			TweakMode::Allow => write!(f, "{}", "allowlist".green()),
+			TweakMode::Deny => write!(f, "{}", "denylist".red()),
+		}
+	}
+}
+
+impl Default for TweakMode {
+	fn default() -> Self {
+		Self::Deny
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibhookerConfig {
+	#[serde(rename = "tweakconfigs", default)]
+	pub tweak_configs: ConfigTypes,
+	#[serde(rename = "webProcessTweaks", default = "default_true")]
+	pub web_process_tweaks: bool,
+	#[serde(
+		rename = "memPrefs",
+		default,
+		skip_serializing_if = "BTreeMap::is_empty"
+	)]
+	pub memory_compat_prefs: BTreeMap<String, bool>,
+	/// Any keys we don't know about, which we keep so that they're written back unchanged.
+	#[serde(flatten)]
+	pub extra: plist::Dictionary,
+}
+
+impl LibhookerConfig {
+	/// Read a libhooker configuration plist.
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let path = path.as_ref();
+		plist::from_file(path).wrap_err_with(|| format!("failed to read {}", path.display()))
+	}
+
+	/// Parse a libhooker configuration plist that's already been read.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		Ok(plist::from_reader(Cursor::new(bytes))?)
+	}
+
+	/// Write this configuration out as a binary plist, the same way libhooker does.
+	pub fn to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+		let path = path.as_ref();
+		plist::to_file_binary(path, self)
+			.wrap_err_with(|| format!("failed to write {}", path.display()))
+	}
+
+	/// Change the configuration of a target, creating it if needed.
+	/// The changes map tweak names to whether they should be set "on" or "off",
+	/// or to None if their entry should be removed.
+	pub fn configure(
+		&mut self,
+		target: &Target,
+		mode: TweakMode,
+		custom_config: Option<bool>,
+		enable_tweaks: Option<bool>,
+		changes: BTreeMap<String, Option<bool>>,
+	) -> &TweakConfig {
+		let tweak_config = target.get_config(&mut self.tweak_configs);
+		tweak_config.allow_or_deny = mode;
+		tweak_config.custom_config = custom_config.unwrap_or(tweak_config.custom_config);
+		tweak_config.enable_tweaks = enable_tweaks.unwrap_or(tweak_config.enable_tweaks);
+		for (tweak, entry) in changes {
+			tweak_config.set_tweak_entry(&tweak, entry);
+		}
+		tweak_config
+	}
+
+	/// Make a tweak load (or not) in the default configuration,
+	/// and if `everywhere` is set, in every other target's custom configuration too.
+	///
+	/// Targets with custom configuration off are skipped, since libhooker ignores their entries,
+	/// and they use the default configuration anyways. They're returned, so they can be pointed out.
+	pub fn set_tweak_loads(&mut self, tweak: &str, load: bool, everywhere: bool) -> Vec<Target> {
+		let configs = &mut self.tweak_configs;
+		configs.default.set_tweak_loads(tweak, load);
+		let mut skipped = Vec::new();
+		if !everywhere {
+			return skipped;
+		}
+		let targets = configs
+			.paths
+			.iter_mut()
+			.map(|(path, tweak_config)| (Target::Executable(path.clone()), tweak_config))
+			.chain(
+				configs
+					.bundles
+					.iter_mut()
+					.map(|(bundle, tweak_config)| (Target::App(bundle.clone()), tweak_config)),
+			);
+		for (target, tweak_config) in targets {
+			if tweak_config.custom_config {
+				tweak_config.set_tweak_loads(tweak, load);
+			} else {
+				skipped.push(target);
+			}
+		}
+		skipped
+	}
+
+	/// Set the compatibility mode of tweaks, mapping tweak names to either
+	/// [COMPAT_LIBHOOKER] or [COMPAT_SUBSTRATE].
+	pub fn set_compat(&mut self, changes: BTreeMap<String, bool>) {
+		self.memory_compat_prefs.extend(changes);
+		// If any entries are true (the default), filter them out of the map,
+		// because saving defaults is a waste of effort and space.
+		self.memory_compat_prefs
+			.retain(|_, compat_mode| *compat_mode != COMPAT_LIBHOOKER);
+	}
+}
+
+impl Default for LibhookerConfig {
+	fn default() -> Self {
+		Self {
+			tweak_configs: ConfigTypes::default(),
+			web_process_tweaks: true,
+			memory_compat_prefs: BTreeMap::default(),
+			extra: plist::Dictionary::default(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConfigTypes {
+	#[serde(default)]
+	pub paths: BTreeMap<PathBuf, TweakConfig>,
+	#[serde(default)]
+	pub bundles: BTreeMap<String, TweakConfig>,
+	// This is only left out if there's nothing in it at all.
+	// Leaving it out whenever every tweak loads anyways (`should_skip`) would throw away
+	// tweak loading being off, it's mode, and any keys we don't know about.
+	// That's also why it isn't filled with an entry for every installed tweak when it's missing:
+	// those entries would then be written into a plist that never had them.
+	#[serde(default, skip_serializing_if = "TweakConfig::is_unset")]
+	pub default: TweakConfig,
+	/// Any keys we don't know about, which we keep so that they're written back unchanged.
+	#[serde(flatten)]
+	pub extra: plist::Dictionary,
+}
+
+impl ConfigTypes {
+	/// Remove the configuration of every app and executable,
+	/// and put the default configuration back to libhooker's defaults.
+	pub fn reset(&mut self) {
+		self.paths.clear();
+		self.bundles.clear();
+		Target::Default.remove_config(self);
+	}
+
+	/// Replace a target's whole configuration with a copy of another target's.
+	/// Returns false, and changes nothing, if the source has no configuration of it's own.
+	pub fn clone_config(&mut self, source: &Target, destination: &Target) -> bool {
+		let mut tweak_config = match source.find_config(self) {
+			Some(tweak_config) => tweak_config.clone(),
+			None => return false,
+		};
+		match (source, destination) {
+			// The default configuration is never a custom configuration.
+			(_, Target::Default) => tweak_config.custom_config = false,
+			// The default configuration doesn't have custom configuration turned on,
+			// but a copy of it is pointless without it, since it'd just fall back to the default anyways.
+			(Target::Default, _) => tweak_config.custom_config = true,
+			_ => {}
+		}
+		destination.apply_config(self, tweak_config);
+		true
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweakConfig {
+	#[serde(rename = "customConfig", default = "default_false")]
+	pub custom_config: bool,
+	#[serde(rename = "enableTweaks", default = "default_true")]
+	pub enable_tweaks: bool,
+	#[serde(rename = "tweakConfigs")]
+	pub tweak_configs: BTreeMap<String, bool>,
+	#[serde(rename = "allowDeny", default)]
+	pub allow_or_deny: TweakMode,
+	/// Any keys we don't know about, which we keep so that they're written back unchanged.
+	#[serde(flatten)]
+	pub extra: plist::Dictionary,
+}
+
+impl TweakConfig {
+	/// Check to see whether a tweak will load or not,
+	/// based on the tweak mode (allowOrDeny) and it's
+	/// boolean value.
+	///
+	/// A tweak without an entry is read as "off", just like libhooker reads it,
+	/// so it loads in denylist mode and doesn't in allowlist mode.
+	/// This used to say a tweak without an entry never loads, which was only right
+	/// because bender used to write an entry for every installed tweak, and it doesn't anymore.
+	pub fn will_tweak_load(&self, name: &str) -> bool {
+		if !self.enable_tweaks {
+			return false;
+		}
+		self.allow_or_deny.check(self.tweak_value(name))
+	}
+
+	/// Get the value libhooker will see for a tweak.
+	/// libhooker reads a missing entry as false, so that's what we do too.
+	pub fn tweak_value(&self, name: &str) -> bool {
+		self.tweak_entry(name).unwrap_or(false)
+	}
+
+	/// Get a tweak's entry as it's written in the configuration:
+	/// Some if it's explicitly set "on" or "off", or None if there's no entry at all.
+	pub fn tweak_entry(&self, name: &str) -> Option<bool> {
+		self.tweak_configs.get(name).copied()
+	}
+
+	/// Set a tweak's entry "on" or "off", or remove it entirely with None.
+	pub fn set_tweak_entry(&mut self, name: &str, entry: Option<bool>) {
+		match entry {
+			Some(value) => {
+				self.tweak_configs.insert(name.to_string(), value);
+			}
+			None => {
+				self.tweak_configs.remove(name);
+			}
+		}
+	}
+
+	/// Set a tweak's entry to whatever makes it load (or not) in this configuration's mode.
+	pub fn set_tweak_loads(&mut self, name: &str, load: bool) {
+		// Checking a value against the mode is it's own inverse: in allowlist mode,
+		// "on" loads the tweak, and in denylist mode, "off" does.
+		let value = self.allow_or_deny.check(load);
+		self.tweak_configs.insert(name.to_string(), value);
+	}
+
+	/// Switch between allowlist and denylist mode, rewriting every entry so that the same tweaks keep loading.
+	/// Tweaks without an entry load in denylist mode but not in allowlist mode,
+	/// so every installed tweak ends up with an entry.
+	pub fn switch_mode(&mut self, mode: TweakMode, installed_tweaks: &[String]) {
+		if mode == self.allow_or_deny {
+			return;
+		}
+		let loads = installed_tweaks
+			.iter()
+			.chain(self.tweak_configs.keys())
+			.map(|tweak| {
+				(
+					tweak.clone(),
+					self.allow_or_deny.check(self.tweak_value(tweak)),
+				)
+			})
+			.collect::<BTreeMap<String, bool>>();
+		self.allow_or_deny = mode;
+		for (tweak, load) in loads {
+			self.set_tweak_loads(&tweak, load);
+		}
+	}
+
+	/// Check to see if this tweak configuration has never been touched,
+	/// in which case there's no point in saving it.
+	pub fn is_unset(&self) -> bool {
+		self.tweak_configs.is_empty()
+			&& self.extra.is_empty()
+			&& !self.custom_config
+			&& self.enable_tweaks
+			&& self.allow_or_deny == TweakMode::default()
+	}
+
+	/// Check to see if this tweak configuration is worth showing.
+	/// If it has no entries, or if everything will load anyways,
+	/// then there's nothing interesting about it.
+	pub fn should_skip(&self) -> bool {
+		self.tweak_configs.is_empty()
+			|| self
+				.tweak_configs
+				.values()
+				.all(|x| self.allow_or_deny.check(*x))
+	}
+}
+
+impl Default for TweakConfig {
+	fn default() -> Self {
+		Self {
+			allow_or_deny: TweakMode::default(),
+			custom_config: false,
+			enable_tweaks: true,
+			tweak_configs: BTreeMap::new(),
+			extra: plist::Dictionary::default(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+	/// Configure the executable located at this path.
+	Executable(PathBuf),
+	/// Configure the app with this bundle ID.
+	App(String),
+	/// Configure the default libhooker configuration.
+	Default,
+}
+
+impl Target {
+	/// Get this target's configuration, creating it if needed.
+	/// A new configuration has no tweak entries at all, since libhooker reads a missing entry as "off" anyways.
+	pub fn get_config<'a>(&self, base_config: &'a mut ConfigTypes) -> &'a mut TweakConfig {
+		match self {
+			Target::Executable(path) => base_config.paths.entry(path.clone()).or_default(),
+			Target::App(bundle) => base_config.bundles.entry(bundle.clone()).or_default(),
+			Target::Default => &mut base_config.default,
+		}
+	}
+
+	pub fn apply_config(&self, base_config: &mut ConfigTypes, tweak_config: TweakConfig) {
+		match self {
+			Target::Executable(path) => {
+				base_config.paths.insert(path.clone(), tweak_config);
+			}
+			Target::App(bundle) => {
+				base_config.bundles.insert(bundle.clone(), tweak_config);
+			}
+			Target::Default => base_config.default = tweak_config,
+		}
+	}
+
+	/// Get the configuration for this target, if it has one.
+	pub fn find_config<'a>(&self, base_config: &'a ConfigTypes) -> Option<&'a TweakConfig> {
+		match self {
+			Target::Executable(path) => base_config.paths.get(path),
+			Target::App(bundle) => base_config.bundles.get(bundle),
+			Target::Default => Some(&base_config.default),
+		}
+	}
+
+	pub fn find_config_mut<'a>(
+		&self,
+		base_config: &'a mut ConfigTypes,
+	) -> Option<&'a mut TweakConfig> {
+		match self {
+			Target::Executable(path) => base_config.paths.get_mut(path),
+			Target::App(bundle) => base_config.bundles.get_mut(bundle),
+			Target::Default => Some(&mut base_config.default),
+		}
+	}
+
+	/// Remove this target's configuration, so that it goes back to using the default configuration.
+	/// The default configuration itself can't be removed, so it goes back to libhooker's defaults instead.
+	pub fn remove_config(&self, base_config: &mut ConfigTypes) -> Option<TweakConfig> {
+		match self {
+			Target::Executable(path) => base_config.paths.remove(path),
+			Target::App(bundle) => base_config.bundles.remove(bundle),
+			Target::Default => {
+				let removed = std::mem::take(&mut base_config.default);
+				// Hold on to anything we don't understand, just like everywhere else.
+				base_config.default.extra = removed.extra.clone();
+				Some(removed)
+			}
+		}
+	}
+
+	pub fn get_tweak_mode(&self, base_config: &ConfigTypes) -> TweakMode {
+		match self {
+			Target::Executable(path) => base_config
+				.paths
+				.get(path)
+				.map(|x| x.allow_or_deny)
+				.unwrap_or_default(),
+			Target::App(bundle) => base_config
+				.bundles
+				.get(bundle)
+				.map(|x| x.allow_or_deny)
+				.unwrap_or_default(),
+			Target::Default => base_config.default.allow_or_deny,
+		}
+	}
+}
+
+impl Target {
+	/// Figure out what the user meant by `target`.
+	/// Returns `None` if it isn't any kind of target we know about.
+	pub fn resolve(
+		target: &str,
+		apps: &dyn AppCatalog,
+		services: &dyn ServiceCatalog,
+	) -> Result<Option<Self>> {
+		let target_path = PathBuf::from(target);
+		let target = target.trim();
+		Ok(match target.to_lowercase().as_str() {
+			"springboard" => Some(Self::Executable(SPRINGBOARD.into())),
+			"default" => Some(Self::Default),
+			_ => {
+				if target_path.is_absolute() && paths::resolve(&target_path).is_file() {
+					Some(Self::Executable(target_path))
+				} else if let Some(bundle_id) = fix_app_name(apps, target)? {
+					Some(Self::App(bundle_id))
+				} else {
+					let normalized_target = target.trim().to_lowercase();
+					services
+						.daemons()?
+						.iter()
+						.find(|daemon| {
+							let normalized_name = daemon.name().map(|x| x.trim().to_lowercase());
+							normalized_name.as_deref() == Some(normalized_target.as_str())
+								|| daemon.label.to_lowercase() == normalized_target
+								|| daemon.executable == target_path
+						})
+						.map(|daemon| Self::Executable(daemon.executable.clone()))
+				}
+			}
+		})
+	}
+
+	/// How libhooker tells this target apart from the others:
+	/// executables by their path, apps by their bundle ID, and the default configuration as "default".
+	pub fn key(&self) -> String {
+		match self {
+			Target::Executable(path) => path.display().to_string(),
+			Target::App(bundle) => bundle.clone(),
+			Target::Default => "default".to_string(),
+		}
+	}
+
+	/// Display this target, using the app's name rather than it's bundle ID if possible.
+	pub fn display<'a>(&'a self, apps: &'a dyn AppCatalog) -> TargetDisplay<'a> {
+		TargetDisplay { target: self, apps }
+	}
+}
+
+// Targets are written out the same way libhooker tells them apart:
+// executables by their path, apps by their bundle ID, and the default configuration as "default".
+impl Serialize for Target {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		match self {
+			Target::Executable(path) => path.serialize(serializer),
+			Target::App(bundle) => bundle.serialize(serializer),
+			Target::Default => serializer.serialize_str("default"),
+		}
+	}
+}
+
+pub struct TargetDisplay<'a> {
+	target: &'a Target,
+	apps: &'a dyn AppCatalog,
+}
+
+impl Display for TargetDisplay<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.target {
+			Target::App(name) => write!(f, "{}", get_friendly_app_name(self.apps, name)),
+			target => write!(f, "{}", target),
+		}
+	}
+}
+
+impl Display for Target {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Target::Executable(path) => {
+				write!(
+					f,
+					"{}",
+					path.file_name()
+						.and_then(OsStr::to_str)
+						.map(|x| x.to_string())
+						.unwrap_or_else(|| path.display().to_string())
+				)
+			}
+			Target::App(name) => write!(f, "{}", name),
+			Target::Default => write!(f, "libhooker defaults"),
+		}
+	}
+}
@@ -0,0 +1,186 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	apps::AppCatalog,
+	daemons::ServiceCatalog,
+	libhooker::{LibhookerConfig, Target, TweakConfig},
+	paths,
+	tweaks::TweakSource,
+};
+use eyre::Result;
+use serde::Serialize;
+use std::{cmp::Reverse, path::Path};
+
+/// How much a lint issue matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+	/// Harmless, but pointless.
+	Info,
+	/// Leftovers that don't do anything anymore.
+	Warning,
+	/// Something that doesn't do what it looks like it does.
+	Error,
+}
+
+/// A problem with a libhooker configuration.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum Issue {
+	/// A target has an entry for a tweak that isn't installed.
+	UninstalledTweak { target: Target, tweak: String },
+	/// A target is an app that isn't installed.
+	MissingApp { target: Target },
+	/// A target is an executable that doesn't exist.
+	MissingExecutable { target: Target },
+	/// An app is configured both by it's bundle ID and by the path to it's executable,
+	/// so it's anyone's guess which configuration libhooker uses.
+	DuplicateTarget { app: Target, executable: Target },
+	/// A target has tweak entries, but libhooker ignores them since custom configuration is off.
+	IgnoredTweaks { target: Target, count: usize },
+	/// A tweak that isn't installed has a compatibility mode.
+	UninstalledCompat { tweak: String },
+}
+
+impl Issue {
+	pub fn severity(&self) -> Severity {
+		match self {
+			Issue::DuplicateTarget { .. } => Severity::Error,
+			Issue::IgnoredTweaks { .. } => Severity::Info,
+			_ => Severity::Warning,
+		}
+	}
+
+	/// Whether [Issue::fix] can do anything about this.
+	pub fn fixable(&self) -> bool {
+		// There's no telling which of the two configurations is the one that's wanted.
+		!matches!(self, Issue::DuplicateTarget { .. })
+	}
+
+	/// Fix the issue by removing whatever's causing it. Returns false if it can't be fixed.
+	pub fn fix(&self, config: &mut LibhookerConfig) -> bool {
+		let configs = &mut config.tweak_configs;
+		match self {
+			Issue::UninstalledTweak { target, tweak } => {
+				if let Some(tweak_config) = target.find_config_mut(configs) {
+					tweak_config.tweak_configs.remove(tweak);
+				}
+			}
+			Issue::MissingApp { target } | Issue::MissingExecutable { target } => {
+				target.remove_config(configs);
+			}
+			Issue::DuplicateTarget { .. } => return false,
+			Issue::IgnoredTweaks { target, .. } => {
+				if let Some(tweak_config) = target.find_config_mut(configs) {
+					tweak_config.tweak_configs.clear();
+				}
+			}
+			Issue::UninstalledCompat { tweak } => {
+				config.memory_compat_prefs.remove(tweak);
+			}
+		}
+		true
+	}
+}
+
+/// Find every problem with a configuration, with the most serious ones first.
+pub fn lint(
+	config: &LibhookerConfig,
+	tweaks: &dyn TweakSource,
+	apps: &dyn AppCatalog,
+	services: &dyn ServiceCatalog,
+) -> Result<Vec<Issue>> {
+	let installed_tweaks = tweaks.tweaks()?;
+	let installed_apps = apps.apps()?;
+	let daemons = services.daemons()?;
+	let configs = &config.tweak_configs;
+	let mut issues = Vec::new();
+
+	let targets = std::iter::once((Target::Default, &configs.default))
+		.chain(
+			configs
+				.paths
+				.iter()
+				.map(|(path, tweak_config)| (Target::Executable(path.clone()), tweak_config)),
+		)
+		.chain(
+			configs
+				.bundles
+				.iter()
+				.map(|(bundle, tweak_config)| (Target::App(bundle.clone()), tweak_config)),
+		)
+		.collect::<Vec<(Target, &TweakConfig)>>();
+	for (target, tweak_config) in targets {
+		match &target {
+			Target::App(bundle) => {
+				let app = installed_apps.iter().find(|app| app.identifier == *bundle);
+				match app {
+					None => issues.push(Issue::MissingApp {
+						target: target.clone(),
+					}),
+					Some(app) => {
+						if let Some(executable) = &app.executable {
+							if configs.paths.contains_key(executable) {
+								issues.push(Issue::DuplicateTarget {
+									app: target.clone(),
+									executable: Target::Executable(executable.clone()),
+								});
+							}
+						}
+					}
+				}
+			}
+			Target::Executable(path) => {
+				// A manifest doesn't come with a filesystem, so knowing about the executable is good enough.
+				let known = paths::resolve(path).is_file()
+					|| daemons.iter().any(|daemon| daemon.executable == *path)
+					|| installed_apps
+						.iter()
+						.any(|app| app.executable.as_deref() == Some(path as &Path));
+				if !known {
+					issues.push(Issue::MissingExecutable {
+						target: target.clone(),
+					});
+				}
+			}
+			Target::Default => {}
+		}
+
+		for tweak in tweak_config.tweak_configs.keys() {
+			if !installed_tweaks.contains(tweak) {
+				issues.push(Issue::UninstalledTweak {
+					target: target.clone(),
+					tweak: tweak.clone(),
+				});
+			}
+		}
+		if !matches!(target, Target::Default)
+			&& !tweak_config.custom_config
+			&& !tweak_config.tweak_configs.is_empty()
+		{
+			issues.push(Issue::IgnoredTweaks {
+				count: tweak_config.tweak_configs.len(),
+				target,
+			});
+		}
+	}
+
+	for tweak in config.memory_compat_prefs.keys() {
+		if !installed_tweaks.contains(tweak) {
+			issues.push(Issue::UninstalledCompat {
+				tweak: tweak.clone(),
+			});
+		}
+	}
+
+	// This is a stable sort, so issues of the same severity stay in order.
+	issues.sort_by_key(|issue| Reverse(issue.severity()));
+	Ok(issues)
+}
@@ -0,0 +1,54 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{apps::App, daemons::Daemon};
+use eyre::{Result, WrapErr};
+use serde::Deserialize;
+use std::path::Path;
+
+/// A plist file describing a device's tweaks, apps, and daemons,
+/// so that bender can be used without the device itself.
+///
+/// ```xml
+/// <dict>
+///   <key>tweaks</key>
+///   <array><string>Example.dylib</string></array>
+///   <key>apps</key>
+///   <array>
+///     <dict>
+///       <key>identifier</key><string>com.apple.mobilesafari</string>
+///       <key>name</key><string>Safari</string>
+///     </dict>
+///   </array>
+///   <key>daemons</key>
+///   <array>
+///     <dict>
+///       <key>label</key><string>com.apple.backboardd</string>
+///       <key>executable</key><string>/usr/libexec/backboardd</string>
+///     </dict>
+///   </array>
+/// </dict>
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Manifest {
+	#[serde(default)]
+	pub tweaks: Vec<String>,
+	#[serde(default)]
+	pub apps: Vec<App>,
+	#[serde(default)]
+	pub daemons: Vec<Daemon>,
+}
+
+impl Manifest {
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let path = path.as_ref();
+		plist::from_file(path)
+			.wrap_err_with(|| format!("failed to read manifest {}", path.display()))
+	}
+}
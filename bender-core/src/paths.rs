@@ -0,0 +1,71 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use once_cell::sync::OnceCell;
+use std::path::{Component, Path, PathBuf};
+
+/// Where libhooker keeps its configuration.
+pub const LIBHOOKER_PLIST: &str = "/var/mobile/Library/Preferences/org.coolstar.libhooker.plist";
+/// The directory libhooker loads tweaks from.
+pub const TWEAK_INJECT: &str = "/usr/lib/TweakInject";
+/// Where bender keeps it's own data, such as configuration snapshots and profiles.
+pub const BENDER_DATA: &str = "/var/mobile/Library/Application Support/bender";
+/// The marker file that Procursus leaves in the root of the filesystem.
+pub const PROCURSUS_STRAPPED: &str = "/.procursus_strapped";
+/// Where dpkg keeps track of every installed package.
+pub const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+
+// The filesystem root that every device path is resolved under.
+// This is set once, from `--root` or `BENDER_ROOT`, before anything else happens.
+static ROOT: OnceCell<PathBuf> = OnceCell::new();
+
+/// Set the root that all device paths will be resolved under.
+/// Only the first call has any effect.
+pub fn set_root(root: PathBuf) {
+	let _ = ROOT.set(root);
+}
+
+/// Get the root that all device paths are resolved under, which is `/` by default.
+pub fn root() -> &'static Path {
+	ROOT.get()
+		.map(PathBuf::as_path)
+		.unwrap_or_else(|| Path::new("/"))
+}
+
+/// Resolve an absolute path on the device to where it actually is under the root.
+/// For example, with a root of `/tmp/dump`, `/usr/lib/TweakInject` becomes `/tmp/dump/usr/lib/TweakInject`.
+pub fn resolve<P: AsRef<Path>>(path: P) -> PathBuf {
+	// Joining an absolute path would replace the root entirely, so we only join the parts after the `/`.
+	let relative: PathBuf = path
+		.as_ref()
+		.components()
+		.filter(|component| !matches!(component, Component::RootDir | Component::Prefix(_)))
+		.collect();
+	root().join(relative)
+}
+
+/// The resolved path to the libhooker configuration plist.
+pub fn libhooker_plist() -> PathBuf {
+	resolve(LIBHOOKER_PLIST)
+}
+
+/// The resolved path to the TweakInject directory.
+pub fn tweak_inject() -> PathBuf {
+	resolve(TWEAK_INJECT)
+}
+
+/// The resolved path to the directory bender keeps configuration snapshots in.
+pub fn history_dir() -> PathBuf {
+	resolve(BENDER_DATA).join("history")
+}
+
+/// The resolved path to the directory bender keeps configuration profiles in.
+pub fn profiles_dir() -> PathBuf {
+	resolve(BENDER_DATA).join("profiles")
+}
@@ -0,0 +1,113 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::libhooker::{ConfigTypes, LibhookerConfig};
+use eyre::{bail, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, ffi::OsStr, path::PathBuf};
+
+/// A named, saved set of tweak configurations and compatibility modes,
+/// which can be switched to all at once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+	#[serde(rename = "tweakconfigs", default)]
+	pub tweak_configs: ConfigTypes,
+	#[serde(
+		rename = "memPrefs",
+		default,
+		skip_serializing_if = "BTreeMap::is_empty"
+	)]
+	pub memory_compat_prefs: BTreeMap<String, bool>,
+}
+
+impl Profile {
+	/// Take the tweak configurations and compatibility modes from a configuration.
+	pub fn from_config(config: &LibhookerConfig) -> Self {
+		Self {
+			tweak_configs: config.tweak_configs.clone(),
+			memory_compat_prefs: config.memory_compat_prefs.clone(),
+		}
+	}
+
+	/// Replace the tweak configurations and compatibility modes of a configuration with this profile's.
+	/// Everything else in the configuration is left alone.
+	pub fn apply_to(&self, config: &mut LibhookerConfig) {
+		config.tweak_configs = self.tweak_configs.clone();
+		config.memory_compat_prefs = self.memory_compat_prefs.clone();
+	}
+}
+
+/// The directory profiles are kept in, as `<name>.plist`.
+pub struct Profiles {
+	dir: PathBuf,
+}
+
+impl Profiles {
+	pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+		Self { dir: dir.into() }
+	}
+
+	/// Get the names of every saved profile, sorted.
+	pub fn list(&self) -> Result<Vec<String>> {
+		// No profiles directory just means no profiles have been saved yet.
+		let entries = match std::fs::read_dir(&self.dir) {
+			Ok(entries) => entries,
+			Err(_) => return Ok(Vec::new()),
+		};
+		let mut names = entries
+			.filter_map(|entry| entry.ok())
+			.map(|entry| entry.path())
+			.filter(|path| path.extension().and_then(OsStr::to_str) == Some("plist"))
+			.filter_map(|path| path.file_stem()?.to_str().map(|name| name.to_string()))
+			.collect::<Vec<String>>();
+		names.sort();
+		Ok(names)
+	}
+
+	/// Read a profile, if it exists.
+	pub fn load(&self, name: &str) -> Result<Option<Profile>> {
+		let path = self.path(name)?;
+		if !path.is_file() {
+			return Ok(None);
+		}
+		plist::from_file(&path)
+			.map(Some)
+			.wrap_err_with(|| format!("failed to read profile {}", path.display()))
+	}
+
+	/// Save a profile, replacing any existing profile with the same name.
+	pub fn save(&self, name: &str, profile: &Profile) -> Result<()> {
+		let path = self.path(name)?;
+		std::fs::create_dir_all(&self.dir)
+			.wrap_err_with(|| format!("failed to create {}", self.dir.display()))?;
+		// Profiles are XML, so that they're easy to look at (or edit) by hand.
+		plist::to_file_xml(&path, profile)
+			.wrap_err_with(|| format!("failed to write profile {}", path.display()))
+	}
+
+	/// Delete a profile. Returns false if there was no such profile.
+	pub fn delete(&self, name: &str) -> Result<bool> {
+		let path = self.path(name)?;
+		if !path.is_file() {
+			return Ok(false);
+		}
+		std::fs::remove_file(&path)
+			.wrap_err_with(|| format!("failed to delete profile {}", path.display()))?;
+		Ok(true)
+	}
+
+	fn path(&self, name: &str) -> Result<PathBuf> {
+		let name = name.trim();
+		// The name is used as a file name, so it had better be a sane one.
+		if name.is_empty() || name.starts_with('.') || name.contains('/') {
+			bail!("'{}' is not a valid profile name", name);
+		}
+		Ok(self.dir.join(format!("{}.plist", name)))
+	}
+}
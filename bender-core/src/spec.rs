@@ -0,0 +1,263 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	apps::AppCatalog,
+	daemons::ServiceCatalog,
+	libhooker::{
+		ConfigTypes, LibhookerConfig, Target, TweakConfig, TweakMode, COMPAT_LIBHOOKER,
+		COMPAT_SUBSTRATE,
+	},
+	tweaks::{fix_tweak_name, TweakSource},
+};
+use eyre::{bail, eyre, Result, WrapErr};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	ffi::OsStr,
+	path::Path,
+};
+
+/// A human-written description of how libhooker should be configured,
+/// which `bender apply` makes the device's configuration match.
+///
+/// Every section that's in the file is the complete truth for that part of the configuration:
+/// if there's a `targets` list, any target that isn't in it loses it's configuration,
+/// and if there's a `compat` table, any tweak that isn't in it goes back to libhooker's default mode.
+/// Sections that aren't in the file are left alone.
+///
+/// ```toml
+/// web_process_tweaks = true
+///
+/// [compat]
+/// Example = "substrate"
+///
+/// [[targets]]
+/// name = "Safari"
+/// mode = "allow"
+/// tweaks = ["Example"]
+///
+/// [[targets]]
+/// name = "backboardd"
+/// enable_tweaks = false
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+	/// Whether tweaks are loaded into WebKit processes.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub web_process_tweaks: Option<bool>,
+	/// The compatibility mode of each tweak.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub compat: Option<BTreeMap<String, CompatMode>>,
+	/// Every target with a configuration of it's own.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub targets: Option<Vec<TargetSpec>>,
+}
+
+/// How one target should be configured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetSpec {
+	/// The target, in any form `bender config` takes:
+	/// an app name or bundle ID, a daemon name, an executable path, "springboard", or "default".
+	pub name: String,
+	/// Whether `tweaks` is an allowlist or a denylist.
+	#[serde(default)]
+	pub mode: ModeSpec,
+	/// Whether the target uses it's own configuration, rather than the default one.
+	/// This is on by default for everything but the default configuration itself.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub custom_config: Option<bool>,
+	/// Whether any tweaks are loaded into the target at all.
+	#[serde(default = "default_true")]
+	pub enable_tweaks: bool,
+	/// The tweaks that are allowed or denied, depending on the mode.
+	#[serde(default)]
+	pub tweaks: Vec<String>,
+}
+
+/// The tweak mode of a target, by name rather than by number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModeSpec {
+	Allow,
+	Deny,
+}
+
+/// The compatibility mode of a tweak, by name rather than by boolean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompatMode {
+	Libhooker,
+	Substrate,
+}
+
+const fn default_true() -> bool {
+	true
+}
+
+impl Default for ModeSpec {
+	fn default() -> Self {
+		TweakMode::default().into()
+	}
+}
+
+impl From<TweakMode> for ModeSpec {
+	fn from(mode: TweakMode) -> Self {
+		match mode {
+			TweakMode::Allow => Self::Allow,
+			TweakMode::Deny => Self::Deny,
+		}
+	}
+}
+
+impl From<ModeSpec> for TweakMode {
+	fn from(mode: ModeSpec) -> Self {
+		match mode {
+			ModeSpec::Allow => Self::Allow,
+			ModeSpec::Deny => Self::Deny,
+		}
+	}
+}
+
+impl From<CompatMode> for bool {
+	fn from(compat_mode: CompatMode) -> Self {
+		match compat_mode {
+			CompatMode::Libhooker => COMPAT_LIBHOOKER,
+			CompatMode::Substrate => COMPAT_SUBSTRATE,
+		}
+	}
+}
+
+impl CompatMode {
+	/// The name of this mode, as it's written in spec files.
+	pub fn name(&self) -> &'static str {
+		match self {
+			CompatMode::Libhooker => "libhooker",
+			CompatMode::Substrate => "substrate",
+		}
+	}
+}
+
+impl From<bool> for CompatMode {
+	fn from(compat_mode: bool) -> Self {
+		if compat_mode == COMPAT_LIBHOOKER {
+			Self::Libhooker
+		} else {
+			Self::Substrate
+		}
+	}
+}
+
+impl Spec {
+	/// Read a spec from a TOML or YAML file, depending on it's extension.
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+		let path = path.as_ref();
+		let contents = std::fs::read_to_string(path)
+			.wrap_err_with(|| format!("failed to read {}", path.display()))?;
+		match path.extension().and_then(OsStr::to_str) {
+			Some("toml") => toml::from_str(&contents).map_err(|err| eyre!(err)),
+			Some("yaml") | Some("yml") => serde_yaml::from_str(&contents).map_err(|err| eyre!(err)),
+			_ => bail!("{} should end in .toml, .yaml, or .yml", path.display()),
+		}
+		.wrap_err_with(|| format!("failed to parse {}", path.display()))
+	}
+
+	/// Work out what the configuration should be, starting from the current one.
+	/// Every target and tweak is resolved the same way as on the command line,
+	/// and it's an error if any of them can't be found.
+	pub fn apply(
+		&self,
+		config: &LibhookerConfig,
+		tweaks: &dyn TweakSource,
+		apps: &dyn AppCatalog,
+		services: &dyn ServiceCatalog,
+	) -> Result<LibhookerConfig> {
+		let mut desired = config.clone();
+		if let Some(web_process_tweaks) = self.web_process_tweaks {
+			desired.web_process_tweaks = web_process_tweaks;
+		}
+
+		if let Some(compat) = &self.compat {
+			desired.memory_compat_prefs = BTreeMap::new();
+			for (tweak, compat_mode) in compat {
+				desired
+					.memory_compat_prefs
+					.insert(resolve_tweak(tweaks, tweak)?, (*compat_mode).into());
+			}
+			// Just like `bender compat`, libhooker's default mode isn't saved.
+			desired
+				.memory_compat_prefs
+				.retain(|_, compat_mode| *compat_mode != COMPAT_LIBHOOKER);
+		}
+
+		if let Some(targets) = &self.targets {
+			// Start from nothing, but hold on to anything we don't understand.
+			let current = &config.tweak_configs;
+			desired.tweak_configs = ConfigTypes {
+				default: TweakConfig {
+					extra: current.default.extra.clone(),
+					..TweakConfig::default()
+				},
+				extra: current.extra.clone(),
+				..ConfigTypes::default()
+			};
+			let mut seen = Vec::new();
+			for target_spec in targets {
+				let target = Target::resolve(&target_spec.name, apps, services)?
+					.ok_or_else(|| eyre!("{} is not a valid target", target_spec.name))?;
+				if seen.contains(&target) {
+					bail!("{} is in the targets more than once", target_spec.name);
+				}
+				let tweak_config =
+					target_spec.to_config(&target, target.find_config(current), tweaks)?;
+				target.apply_config(&mut desired.tweak_configs, tweak_config);
+				seen.push(target);
+			}
+		}
+		Ok(desired)
+	}
+}
+
+impl TargetSpec {
+	/// Turn this into a tweak configuration, updating the existing one if there is one.
+	/// Updating rather than replacing means that applying the same spec twice changes nothing.
+	fn to_config(
+		&self,
+		target: &Target,
+		existing: Option<&TweakConfig>,
+		tweaks: &dyn TweakSource,
+	) -> Result<TweakConfig> {
+		let listed = self
+			.tweaks
+			.iter()
+			.map(|tweak| resolve_tweak(tweaks, tweak))
+			.collect::<Result<BTreeSet<String>>>()?;
+		let mut config = existing.cloned().unwrap_or_default();
+		config.allow_or_deny = self.mode.into();
+		config.custom_config = self
+			.custom_config
+			.unwrap_or(!matches!(target, Target::Default));
+		config.enable_tweaks = self.enable_tweaks;
+		// Tweaks that already have an entry but aren't listed are set "off", rather than removed,
+		// since libhooker sees those the same way.
+		for (tweak, value) in config.tweak_configs.iter_mut() {
+			*value = listed.contains(tweak);
+		}
+		config
+			.tweak_configs
+			.extend(listed.into_iter().map(|tweak| (tweak, true)));
+		Ok(config)
+	}
+}
+
+fn resolve_tweak(tweaks: &dyn TweakSource, tweak: &str) -> Result<String> {
+	fix_tweak_name(tweaks, tweak)?.ok_or_else(|| eyre!("tweak {} is not installed", tweak))
+}
@@ -0,0 +1,247 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{history::History, libhooker::LibhookerConfig};
+use eyre::{bail, Result, WrapErr};
+use std::{
+	collections::hash_map::DefaultHasher,
+	ffi::CString,
+	fs::{File, Metadata},
+	hash::{Hash, Hasher},
+	io::{ErrorKind, Read, Write},
+	os::unix::{ffi::OsStrExt, fs::MetadataExt, io::AsRawFd},
+	path::{Path, PathBuf},
+	time::SystemTime,
+};
+
+/// What the configuration file looked like when we read it,
+/// so that we can tell if something else changed it behind our back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Fingerprint {
+	modified: Option<SystemTime>,
+	len: usize,
+	hash: u64,
+}
+
+impl Fingerprint {
+	fn new(file: &File, bytes: &[u8]) -> Self {
+		let mut hasher = DefaultHasher::new();
+		bytes.hash(&mut hasher);
+		Self {
+			modified: file
+				.metadata()
+				.and_then(|metadata| metadata.modified())
+				.ok(),
+			len: bytes.len(),
+			hash: hasher.finish(),
+		}
+	}
+}
+
+/// The libhooker configuration plist on disk.
+///
+/// This remembers what the file looked like when it was loaded, and saving refuses to
+/// overwrite it if anything else (such as libhooker-configurator) has changed it since.
+/// Saves are atomic: the new configuration is written to a temporary file and renamed into place,
+/// so the plist is never left half-written, and it keeps the owner and mode of the original.
+pub struct ConfigFile {
+	path: PathBuf,
+	// None if the file didn't exist when we loaded it.
+	fingerprint: Option<Fingerprint>,
+	history: Option<History>,
+}
+
+impl ConfigFile {
+	pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+		Self {
+			path: path.into(),
+			fingerprint: None,
+			history: None,
+		}
+	}
+
+	/// Take a snapshot of the configuration in this history before every save.
+	pub fn with_history(mut self, history: History) -> Self {
+		self.history = Some(history);
+		self
+	}
+
+	/// Read the configuration, remembering what the file looked like.
+	/// If the file doesn't exist yet, the default configuration is returned.
+	pub fn load(&mut self) -> Result<LibhookerConfig> {
+		match self.load_raw()? {
+			Some(bytes) => LibhookerConfig::from_bytes(&bytes)
+				.wrap_err_with(|| format!("failed to read {}", self.path.display())),
+			None => Ok(LibhookerConfig::default()),
+		}
+	}
+
+	/// Read the raw bytes of the configuration, without parsing them, remembering what the file looked like.
+	/// This works even if the plist is broken. If the file doesn't exist yet, None is returned.
+	pub fn load_raw(&mut self) -> Result<Option<Vec<u8>>> {
+		let (fingerprint, bytes) = self.read()?;
+		self.fingerprint = fingerprint;
+		Ok(bytes)
+	}
+
+	/// Atomically write the configuration back out as a binary plist.
+	/// This fails if the file has been changed since it was loaded.
+	///
+	/// If there's a summary of the change, the configuration as it was before is saved to the history first.
+	pub fn save(&mut self, config: &LibhookerConfig, summary: Option<&str>) -> Result<()> {
+		let mut bytes = Vec::new();
+		plist::to_writer_binary(&mut bytes, config)
+			.wrap_err_with(|| format!("failed to serialize {}", self.path.display()))?;
+		self.save_raw(&bytes, summary)
+	}
+
+	/// Atomically replace the configuration with the raw bytes of a plist, exactly as they are,
+	/// the same way as [ConfigFile::save].
+	pub fn save_raw(&mut self, bytes: &[u8], summary: Option<&str>) -> Result<()> {
+		let dir = self
+			.path
+			.parent()
+			.map(Path::to_path_buf)
+			.unwrap_or_else(|| PathBuf::from("."));
+		// We lock the directory rather than the file itself, because the file gets replaced.
+		// The lock is released when this is dropped.
+		let lock =
+			File::open(&dir).wrap_err_with(|| format!("failed to open {}", dir.display()))?;
+		lock_exclusive(&lock).wrap_err_with(|| format!("failed to lock {}", dir.display()))?;
+
+		// Now that nobody else using bender can touch it, make sure nothing has changed.
+		let (fingerprint, previous) = self.read()?;
+		if fingerprint != self.fingerprint {
+			bail!(
+				"{} was changed by something else while bender was running, try again",
+				self.path.display()
+			);
+		}
+
+		if let (Some(history), Some(summary)) = (&self.history, summary) {
+			// If there wasn't a configuration yet, then it was effectively the default one.
+			let previous = match previous {
+				Some(previous) => previous,
+				None => {
+					let mut bytes = Vec::new();
+					plist::to_writer_binary(&mut bytes, &LibhookerConfig::default())?;
+					bytes
+				}
+			};
+			history.record(&previous, summary)?;
+		}
+
+		let temp_path = dir.join(format!(
+			".{}.bender-{}",
+			self.path
+				.file_name()
+				.map(|name| name.to_string_lossy())
+				.unwrap_or_default(),
+			std::process::id()
+		));
+		let result = self.replace_with(&dir, &temp_path, bytes);
+		if result.is_err() {
+			let _ = std::fs::remove_file(&temp_path);
+		}
+		result
+	}
+
+	/// Write the bytes to the temporary file, and then rename it over the configuration file.
+	fn replace_with(&mut self, dir: &Path, temp_path: &Path, bytes: &[u8]) -> Result<()> {
+		// The new file should look just like the old one, or the directory if there is no old one.
+		// Otherwise, running bender as root would leave a file that libhooker (as mobile) can't touch.
+		let (owner, mode) = match std::fs::metadata(&self.path) {
+			Ok(metadata) => (Owner::of(&metadata), Some(metadata.permissions())),
+			Err(_) => {
+				let metadata = std::fs::metadata(dir)
+					.wrap_err_with(|| format!("failed to read {}", dir.display()))?;
+				(Owner::of(&metadata), None)
+			}
+		};
+
+		let mut file = File::create(temp_path)
+			.wrap_err_with(|| format!("failed to create {}", temp_path.display()))?;
+		file.write_all(bytes)
+			.and_then(|_| file.sync_all())
+			.wrap_err_with(|| format!("failed to write {}", temp_path.display()))?;
+		if let Some(mode) = mode {
+			file.set_permissions(mode).wrap_err_with(|| {
+				format!("failed to set permissions of {}", temp_path.display())
+			})?;
+		}
+		owner
+			.apply(temp_path)
+			.wrap_err_with(|| format!("failed to set owner of {}", temp_path.display()))?;
+
+		std::fs::rename(temp_path, &self.path)
+			.wrap_err_with(|| format!("failed to replace {}", self.path.display()))?;
+		// Make sure the rename itself hits the disk too.
+		if let Ok(dir) = File::open(dir) {
+			let _ = dir.sync_all();
+		}
+
+		// If we save again, it should be against what we just wrote.
+		let (fingerprint, _) = self.read()?;
+		self.fingerprint = fingerprint;
+		Ok(())
+	}
+
+	/// Read the raw configuration file, if it exists.
+	fn read(&self) -> Result<(Option<Fingerprint>, Option<Vec<u8>>)> {
+		let mut file = match File::open(&self.path) {
+			Ok(file) => file,
+			Err(err) if err.kind() == ErrorKind::NotFound => return Ok((None, None)),
+			Err(err) => {
+				return Err(err).wrap_err_with(|| format!("failed to open {}", self.path.display()))
+			}
+		};
+		let mut bytes = Vec::new();
+		file.read_to_end(&mut bytes)
+			.wrap_err_with(|| format!("failed to read {}", self.path.display()))?;
+		Ok((Some(Fingerprint::new(&file, &bytes)), Some(bytes)))
+	}
+}
+
+fn lock_exclusive(file: &File) -> std::io::Result<()> {
+	// This blocks until whoever else has the lock is done.
+	if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+		Ok(())
+	} else {
+		Err(std::io::Error::last_os_error())
+	}
+}
+
+/// The owner of a file.
+struct Owner {
+	uid: u32,
+	gid: u32,
+}
+
+impl Owner {
+	fn of(metadata: &Metadata) -> Self {
+		Self {
+			uid: metadata.uid(),
+			gid: metadata.gid(),
+		}
+	}
+
+	fn apply(&self, path: &Path) -> std::io::Result<()> {
+		// There's nothing to do if it's already owned by the right user.
+		let metadata = std::fs::metadata(path)?;
+		if metadata.uid() == self.uid && metadata.gid() == self.gid {
+			return Ok(());
+		}
+		let path = CString::new(path.as_os_str().as_bytes())?;
+		if unsafe { libc::chown(path.as_ptr(), self.uid, self.gid) } == 0 {
+			Ok(())
+		} else {
+			Err(std::io::Error::last_os_error())
+		}
+	}
+}
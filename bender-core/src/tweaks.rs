@@ -0,0 +1,127 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::filter::TweakFilter;
+use eyre::{Result, WrapErr};
+use once_cell::sync::OnceCell;
+use std::{ffi::OsStr, path::PathBuf};
+
+/// Something that knows which tweaks are installed.
+pub trait TweakSource {
+	/// Get the file names of all installed tweak dylibs.
+	fn tweaks(&self) -> Result<&[String]>;
+
+	/// Get the filter that decides which processes a tweak is injected into,
+	/// if we know it.
+	fn filter(&self, _tweak: &str) -> Option<TweakFilter> {
+		None
+	}
+}
+
+// A plain list of tweak names is a perfectly fine tweak source,
+// which is handy for fixtures and manifests.
+impl TweakSource for Vec<String> {
+	fn tweaks(&self) -> Result<&[String]> {
+		Ok(self)
+	}
+}
+
+/// Reads the installed tweaks from a TweakInject directory,
+/// such as `/usr/lib/TweakInject` on a live device.
+pub struct TweakInjectDir {
+	path: PathBuf,
+	tweaks: OnceCell<Vec<String>>,
+}
+
+impl TweakInjectDir {
+	pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+		Self {
+			path: path.into(),
+			tweaks: OnceCell::new(),
+		}
+	}
+}
+
+impl TweakSource for TweakInjectDir {
+	fn tweaks(&self) -> Result<&[String]> {
+		// The directory is only read on first use, then reused for subsequent uses.
+		self.tweaks
+			.get_or_try_init(|| {
+				let mut tweaks = Vec::new();
+				// Start to read the contents of the TweakInject directory
+				let dir_contents = std::fs::read_dir(&self.path).wrap_err_with(|| {
+					format!(
+						"{} dir not found, are you not using libhooker?",
+						self.path.display()
+					)
+				})?;
+				// Now, iterate through all the files in TweakInject
+				for entry in dir_contents {
+					if let Some(file_name) = entry
+						.ok()
+						// Ensure that this entry is a file
+						.filter(|entry| entry.path().is_file())
+						// Ensure that this entry is has a ".dylib" extension
+						.filter(|entry| {
+							entry.path().extension().and_then(OsStr::to_str) == Some("dylib")
+						})
+						// Convert the path to just the file name
+						.map(|entry| entry.file_name())
+						// Convert the file name, which is an OsString, to a normal String
+						.map(|file_name| file_name.to_string_lossy().to_string())
+					{
+						// Well, this is a dylib, so it's a tweak, push it to the vec!
+						tweaks.push(file_name);
+					}
+				}
+				tweaks.sort();
+				Ok(tweaks)
+			})
+			.map(Vec::as_slice)
+	}
+
+	fn filter(&self, tweak: &str) -> Option<TweakFilter> {
+		// The filter plist sits right next to the tweak dylib, with the same name.
+		TweakFilter::from_file(self.path.join(tweak).with_extension("plist"))
+	}
+}
+
+pub fn fix_tweak_name(source: &dyn TweakSource, input: &str) -> Result<Option<String>> {
+	let tweaks = source.tweaks()?;
+	let input = input.trim();
+	if tweaks.iter().any(|tweak| tweak == input) {
+		// This tweak name is already correct, just return it.
+		return Ok(Some(input.to_string()));
+	}
+	let with_extension = [input, "dylib"].join(".");
+	if tweaks.contains(&with_extension) {
+		// Tweak name is just missing the dylib extension, add it.
+		return Ok(Some(with_extension));
+	}
+	// Hm, we might have to look a bit harder. Let's look at *ALL* tweak dylibs
+	let normalized_input = input.to_lowercase();
+	for file_name in tweaks {
+		// First, we do a case-insensitive comparison on full file names.
+		let normalized_file_name = file_name.trim().to_lowercase();
+		// If the case-insensitive comparison matches, then we return the name of the actual file.
+		if normalized_file_name == normalized_input {
+			return Ok(Some(file_name.clone()));
+		}
+		// Next, we check the file stem, which is the file name without the extension.
+		// Same deal, if the case-insensitive comparison matches, then we return the name of the actual file.
+		let normalized_file_stem = normalized_file_name
+			.strip_suffix(".dylib")
+			.unwrap_or(&normalized_file_name);
+		if normalized_file_stem == normalized_input {
+			return Ok(Some(file_name.clone()));
+		}
+	}
+	// Welp, we couldn't find it.
+	Ok(None)
+}
@@ -0,0 +1,148 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that apps are found from their bundles wherever iOS puts them,
+// and named the same way the home screen names them.
+
+mod common;
+
+use bender_core::apps::{App, AppCatalog, BundleApps};
+use common::{write, SAFARI};
+use plist::{Dictionary, Value};
+use std::path::{Path, PathBuf};
+
+const CONTAINER: &str = "/var/containers/Bundle/Application/0A1B2C3D-4E5F-6071-8293-A4B5C6D7E8F9";
+
+/// The XML of an `Info.plist` with the given keys.
+fn info_plist(keys: &[(&str, &str)]) -> Vec<u8> {
+	let plist = keys
+		.iter()
+		.map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+		.collect::<Dictionary>();
+	let mut bytes = Vec::new();
+	Value::Dictionary(plist)
+		.to_writer_xml(&mut bytes)
+		.expect("failed to write Info.plist");
+	bytes
+}
+
+fn setup(root: &Path) {
+	// A system app, with everything filled in.
+	write(
+		root,
+		"/Applications/MobileSafari.app/Info.plist",
+		info_plist(&[
+			("CFBundleIdentifier", "com.apple.mobilesafari"),
+			("CFBundleDisplayName", "Safari"),
+			("CFBundleName", "MobileSafari"),
+			("CFBundleExecutable", "MobileSafari"),
+			("CFBundleShortVersionString", "14.0"),
+			("CFBundleVersion", "8610.1"),
+		]),
+	);
+	// Only an identifier, so that's all there is to call it.
+	write(
+		root,
+		"/Applications/Bare.app/Info.plist",
+		info_plist(&[("CFBundleIdentifier", "com.example.bare")]),
+	);
+	// Not an app, or not one we can read.
+	write(root, "/Applications/Broken.app/Info.plist", "not a plist");
+	write(root, "/Applications/Empty.app/.keep", "");
+	write(
+		root,
+		"/Applications/NotAnApp/Info.plist",
+		info_plist(&[("CFBundleIdentifier", "com.example.notanapp")]),
+	);
+
+	// App Store apps each get their own container, next to their metadata.
+	let container = Path::new(CONTAINER);
+	write(
+		root,
+		container.join("Foo.app/Info.plist"),
+		info_plist(&[
+			("CFBundleIdentifier", "com.example.foo"),
+			("CFBundleName", "Foo"),
+			("CFBundleExecutable", "FooExec"),
+			("CFBundleVersion", "12"),
+		]),
+	);
+	write(root, container.join("iTunesMetadata.plist"), "");
+	write(
+		root,
+		"/var/containers/Bundle/Application/F9E8D7C6-B5A4-9382-7160-5F4E3D2C1B0A/Bar.app/Info.plist",
+		info_plist(&[
+			("CFBundleIdentifier", "com.example.bar"),
+			("CFBundleExecutable", "BarExec"),
+		]),
+	);
+}
+
+fn apps() -> Vec<App> {
+	common::device("apps", setup);
+	BundleApps::default().apps().unwrap().to_vec()
+}
+
+fn find(identifier: &str) -> App {
+	apps()
+		.into_iter()
+		.find(|app| app.identifier == identifier)
+		.unwrap()
+}
+
+#[test]
+fn both_layouts() {
+	// Sorted by name, and only bundles with an Info.plist we can read.
+	assert_eq!(
+		apps()
+			.iter()
+			.map(|app| app.identifier.as_str())
+			.collect::<Vec<_>>(),
+		[
+			"com.example.bar",
+			"com.example.foo",
+			"com.apple.mobilesafari",
+			"com.example.bare",
+		]
+	);
+}
+
+#[test]
+fn names() {
+	// The display name, then the bundle name, then the executable, then the identifier.
+	assert_eq!(find("com.apple.mobilesafari").name, "Safari");
+	assert_eq!(find("com.example.foo").name, "Foo");
+	assert_eq!(find("com.example.bar").name, "BarExec");
+	assert_eq!(find("com.example.bare").name, "com.example.bare");
+}
+
+#[test]
+fn executables() {
+	// Executables are paths on the device, not under the root.
+	assert_eq!(
+		find("com.apple.mobilesafari").executable,
+		Some(PathBuf::from(SAFARI))
+	);
+	assert_eq!(
+		find("com.example.foo").executable,
+		Some(Path::new(CONTAINER).join("Foo.app/FooExec"))
+	);
+	assert_eq!(find("com.example.bare").executable, None);
+}
+
+#[test]
+fn versions() {
+	// The version people see wins over the build number.
+	assert_eq!(
+		find("com.apple.mobilesafari").version.as_deref(),
+		Some("14.0")
+	);
+	assert_eq!(find("com.example.foo").version.as_deref(), Some("12"));
+	assert_eq!(find("com.example.bar").version, None);
+}
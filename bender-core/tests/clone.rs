@@ -0,0 +1,166 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender clone` copies a configuration exactly, between every kind of target,
+// and that `bender reset` throws away what it's told to and nothing else.
+
+mod common;
+
+use bender_core::{
+	libhooker::{LibhookerConfig, Target, TweakConfig, TweakMode},
+	store::ConfigFile,
+};
+use common::{TempFile, BACKBOARDD};
+use plist::Value;
+use std::path::PathBuf;
+
+fn safari() -> Target {
+	Target::App("com.apple.mobilesafari".to_string())
+}
+
+fn backboardd() -> Target {
+	Target::Executable(PathBuf::from(BACKBOARDD))
+}
+
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	let configs = &mut config.tweak_configs;
+	configs.default.set_tweak_entry("Alpha.dylib", Some(false));
+	configs
+		.default
+		.extra
+		.insert("futureDefaultKey".to_string(), Value::Boolean(true));
+	configs
+		.extra
+		.insert("futureTargetKind".to_string(), Value::Boolean(true));
+	let mut safari_config = TweakConfig {
+		custom_config: true,
+		enable_tweaks: false,
+		allow_or_deny: TweakMode::Allow,
+		..TweakConfig::default()
+	};
+	safari_config.set_tweak_entry("Beta.dylib", Some(true));
+	safari_config.set_tweak_entry("Gamma.dylib", Some(false));
+	safari_config
+		.extra
+		.insert("futureKey".to_string(), Value::Integer(1.into()));
+	safari().apply_config(configs, safari_config);
+	config
+}
+
+/// Everything about a configuration except whether it's a custom configuration.
+fn same_besides_custom_config(a: &TweakConfig, b: &TweakConfig) -> bool {
+	a.enable_tweaks == b.enable_tweaks
+		&& a.allow_or_deny == b.allow_or_deny
+		&& a.tweak_configs == b.tweak_configs
+		&& a.extra == b.extra
+}
+
+#[test]
+fn between_apps_and_executables() {
+	let mut config = sample();
+	let configs = &mut config.tweak_configs;
+	assert!(configs.clone_config(&safari(), &backboardd()));
+	let copy = backboardd().find_config(configs).unwrap();
+	let original = safari().find_config(configs).unwrap();
+	assert!(same_besides_custom_config(copy, original));
+	assert!(copy.custom_config);
+
+	// And back again, with custom configuration off staying off.
+	let mut copy = copy.clone();
+	copy.custom_config = false;
+	backboardd().apply_config(configs, copy);
+	let bundle = Target::App("com.example.foo".to_string());
+	assert!(configs.clone_config(&backboardd(), &bundle));
+	assert!(!bundle.find_config(configs).unwrap().custom_config);
+}
+
+#[test]
+fn into_the_default_configuration() {
+	let mut config = sample();
+	let configs = &mut config.tweak_configs;
+	assert!(configs.clone_config(&safari(), &Target::Default));
+	// libhooker doesn't look at custom configuration in the default configuration, so it's turned off.
+	assert!(!configs.default.custom_config);
+	assert!(same_besides_custom_config(
+		&configs.default,
+		safari().find_config(configs).unwrap()
+	));
+}
+
+#[test]
+fn out_of_the_default_configuration() {
+	let mut config = sample();
+	let configs = &mut config.tweak_configs;
+	assert!(configs.clone_config(&Target::Default, &backboardd()));
+	// A copy of the default configuration does nothing without custom configuration on.
+	let copy = backboardd().find_config(configs).unwrap();
+	assert!(copy.custom_config);
+	assert!(same_besides_custom_config(copy, &configs.default));
+}
+
+#[test]
+fn nothing_to_clone() {
+	let mut config = sample();
+	let configs = &mut config.tweak_configs;
+	let before = configs.clone();
+	assert!(!configs.clone_config(&backboardd(), &safari()));
+	assert!(same_besides_custom_config(
+		safari().find_config(configs).unwrap(),
+		safari().find_config(&before).unwrap()
+	));
+	assert!(backboardd().find_config(configs).is_none());
+}
+
+#[test]
+fn cloning_keeps_the_source() {
+	// Making Safari the default configuration doesn't make Safari's own configuration redundant,
+	// it has to still be there after saving, so it doesn't change along with the default later.
+	let file = TempFile::empty("clone.plist");
+	let mut config_file = ConfigFile::new(&file.0);
+	let mut config = sample();
+	config
+		.tweak_configs
+		.clone_config(&safari(), &Target::Default);
+	config_file.save(&config, None).unwrap();
+	let saved = ConfigFile::new(&file.0).load().unwrap();
+	assert!(safari().find_config(&saved.tweak_configs).is_some());
+	assert_eq!(saved.tweak_configs.default.allow_or_deny, TweakMode::Allow);
+}
+
+#[test]
+fn resetting() {
+	let mut config = sample();
+	let configs = &mut config.tweak_configs;
+	safari().remove_config(configs);
+	assert!(safari().find_config(configs).is_none());
+
+	// The default configuration goes back to libhooker's defaults, except for keys we don't know about.
+	Target::Default.remove_config(configs);
+	assert!(configs.default.tweak_configs.is_empty());
+	assert_eq!(
+		configs.default.extra.get("futureDefaultKey"),
+		Some(&Value::Boolean(true))
+	);
+}
+
+#[test]
+fn resetting_everything() {
+	let mut config = sample();
+	config.tweak_configs.clone_config(&safari(), &backboardd());
+	let configs = &mut config.tweak_configs;
+	configs.reset();
+	assert!(configs.paths.is_empty());
+	assert!(configs.bundles.is_empty());
+	assert!(configs.default.tweak_configs.is_empty());
+	assert_eq!(configs.default.allow_or_deny, TweakMode::Deny);
+	// Unknown keys are kept, both in the default configuration and alongside the targets.
+	assert!(configs.default.extra.contains_key("futureDefaultKey"));
+	assert!(configs.extra.contains_key("futureTargetKind"));
+}
@@ -0,0 +1,130 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// Everything the tests share: temporary files, and a pretend device to resolve targets against.
+// Not every test uses all of it, which is fine.
+#![allow(dead_code)]
+
+use bender_core::{apps::App, daemons::Daemon, paths};
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
+
+pub const SAFARI: &str = "/Applications/MobileSafari.app/MobileSafari";
+pub const BACKBOARDD: &str = "/usr/libexec/backboardd";
+
+/// Where a temporary file or directory goes, so that tests running at the same time don't trip over each other.
+fn temp_path(name: &str) -> PathBuf {
+	std::env::temp_dir().join(format!("bender-test-{}-{}", std::process::id(), name))
+}
+
+/// A temporary file, which is deleted when dropped.
+pub struct TempFile(pub PathBuf);
+
+impl TempFile {
+	/// A temporary file with the given contents.
+	pub fn new(name: &str, contents: &str) -> Self {
+		let file = Self::empty(name);
+		std::fs::write(&file.0, contents).expect("failed to write temporary file");
+		file
+	}
+
+	/// A path for a temporary file, without creating it.
+	pub fn empty(name: &str) -> Self {
+		let path = temp_path(name);
+		let _ = std::fs::remove_file(&path);
+		Self(path)
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_file(&self.0);
+	}
+}
+
+/// A temporary directory, which is deleted (with everything in it) when dropped.
+pub struct TempDir(pub PathBuf);
+
+impl TempDir {
+	pub fn new(name: &str) -> Self {
+		let path = temp_path(name);
+		let _ = std::fs::remove_dir_all(&path);
+		std::fs::create_dir_all(&path).expect("failed to create temporary directory");
+		Self(path)
+	}
+
+	/// Write a file somewhere in the directory, the same way as [write].
+	pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, contents: C) -> PathBuf {
+		write(&self.0, path, contents)
+	}
+}
+
+impl Drop for TempDir {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.0);
+	}
+}
+
+/// Write a file somewhere under a directory, creating every directory on the way.
+/// Absolute paths are put under the directory too, like they would be under `--root`.
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(dir: &Path, path: P, contents: C) -> PathBuf {
+	let path = path.as_ref();
+	let path = dir.join(path.strip_prefix("/").unwrap_or(path));
+	std::fs::create_dir_all(path.parent().unwrap()).expect("failed to create directory");
+	std::fs::write(&path, contents).expect("failed to write file");
+	path
+}
+
+/// A pretend device that every device path is resolved under, just like `--root`.
+///
+/// There's only one root for the whole test binary, so the device is set up once, by whichever test gets here first.
+/// It's kept with the rest of Cargo's output rather than being cleaned up, since there's no telling which test is last.
+pub fn device(name: &str, setup: fn(&Path)) -> &'static Path {
+	static DEVICE: OnceCell<PathBuf> = OnceCell::new();
+	DEVICE.get_or_init(|| {
+		let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+		let _ = std::fs::remove_dir_all(&root);
+		std::fs::create_dir_all(&root).expect("failed to create device");
+		setup(&root);
+		paths::set_root(root.clone());
+		root
+	})
+}
+
+pub fn tweaks() -> Vec<String> {
+	vec![
+		"Alpha.dylib".to_string(),
+		"Beta.dylib".to_string(),
+		"Gamma.dylib".to_string(),
+	]
+}
+
+pub fn apps() -> Vec<App> {
+	vec![
+		App {
+			identifier: "com.apple.mobilesafari".to_string(),
+			name: "Safari".to_string(),
+			executable: Some(PathBuf::from(SAFARI)),
+			version: None,
+		},
+		App {
+			identifier: "com.example.foo".to_string(),
+			name: "Foo App".to_string(),
+			executable: None,
+			version: Some("1.0".to_string()),
+		},
+	]
+}
+
+pub fn daemons() -> Vec<Daemon> {
+	vec![Daemon {
+		label: "com.apple.backboardd".to_string(),
+		executable: PathBuf::from(BACKBOARDD),
+	}]
+}
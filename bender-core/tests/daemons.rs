@@ -0,0 +1,161 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that daemons are found from their launchd plists the way launchd itself reads them,
+// and that anything launchd couldn't run is left out.
+
+mod common;
+
+use bender_core::daemons::{LaunchDaemonPlists, ServiceCatalog};
+use common::{write, BACKBOARDD};
+use plist::{Dictionary, Value};
+use std::path::{Path, PathBuf};
+
+/// The XML of a launchd plist.
+fn launchd_plist(label: &str, program: Option<&str>, arguments: &[&str]) -> Vec<u8> {
+	let mut plist = Dictionary::new();
+	plist.insert("Label".to_string(), Value::String(label.to_string()));
+	if let Some(program) = program {
+		plist.insert("Program".to_string(), Value::String(program.to_string()));
+	}
+	if !arguments.is_empty() {
+		plist.insert(
+			"ProgramArguments".to_string(),
+			Value::Array(
+				arguments
+					.iter()
+					.map(|argument| Value::String(argument.to_string()))
+					.collect(),
+			),
+		);
+	}
+	let mut bytes = Vec::new();
+	Value::Dictionary(plist)
+		.to_writer_xml(&mut bytes)
+		.expect("failed to write launchd plist");
+	bytes
+}
+
+fn setup(root: &Path) {
+	let daemons = Path::new("/System/Library/LaunchDaemons");
+	// Just `ProgramArguments`, like most of Apple's daemons.
+	write(
+		root,
+		daemons.join("com.apple.backboardd.plist"),
+		launchd_plist("com.apple.backboardd", None, &[BACKBOARDD]),
+	);
+	write(root, BACKBOARDD, "");
+	// `Program` wins over `ProgramArguments`, just like it does for launchd.
+	write(
+		root,
+		daemons.join("com.example.program.plist"),
+		launchd_plist(
+			"com.example.program",
+			Some("/usr/libexec/programd"),
+			&["/usr/libexec/argumentd", "--flag"],
+		),
+	);
+	write(root, "/usr/libexec/programd", "");
+	write(root, "/usr/libexec/argumentd", "");
+	// Daemons installed by packages live somewhere else.
+	write(
+		root,
+		"/Library/LaunchDaemons/com.example.thirdparty.plist",
+		launchd_plist("com.example.thirdparty", None, &["/usr/sbin/thirdpartyd"]),
+	);
+	write(root, "/usr/sbin/thirdpartyd", "");
+
+	// launchd would look a relative executable up in it's PATH, which we can't do.
+	write(
+		root,
+		daemons.join("com.example.relative.plist"),
+		launchd_plist("com.example.relative", None, &["relatived"]),
+	);
+	write(root, "/usr/libexec/relatived", "");
+	// The executable has to exist on the device, not on whatever's running the tests.
+	write(
+		root,
+		daemons.join("com.example.missing.plist"),
+		launchd_plist("com.example.missing", Some("/bin/sh"), &[]),
+	);
+	write(
+		root,
+		daemons.join("com.example.nothing.plist"),
+		launchd_plist("com.example.nothing", None, &[]),
+	);
+	// Plists that can't be read are skipped, rather than taking every other daemon down with them.
+	write(
+		root,
+		daemons.join("com.example.broken.plist"),
+		"not a plist",
+	);
+	write(
+		root,
+		daemons.join("com.example.unlabeled.plist"),
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Program</key><string>/usr/libexec/programd</string>
+</dict>
+</plist>"#,
+	);
+	write(root, daemons.join("README"), "not a plist either");
+	// And daemons that aren't worth tweaking are always left out.
+	write(
+		root,
+		daemons.join("jailbreakd.plist"),
+		launchd_plist("jailbreakd", Some("/usr/libexec/jailbreakd"), &[]),
+	);
+	write(root, "/usr/libexec/jailbreakd", "");
+}
+
+fn daemons() -> Vec<(String, PathBuf)> {
+	common::device("daemons", setup);
+	LaunchDaemonPlists::default()
+		.daemons()
+		.unwrap()
+		.iter()
+		.map(|daemon| (daemon.label.clone(), daemon.executable.clone()))
+		.collect()
+}
+
+#[test]
+fn launchd_plists() {
+	// Sorted by executable, with every executable as a path on the device.
+	assert_eq!(
+		daemons(),
+		vec![
+			(
+				"com.apple.backboardd".to_string(),
+				PathBuf::from(BACKBOARDD)
+			),
+			(
+				"com.example.program".to_string(),
+				PathBuf::from("/usr/libexec/programd")
+			),
+			(
+				"com.example.thirdparty".to_string(),
+				PathBuf::from("/usr/sbin/thirdpartyd")
+			),
+		]
+	);
+}
+
+#[test]
+fn executables_are_resolved_under_the_root() {
+	let root = common::device("daemons", setup);
+	for (_, executable) in daemons() {
+		assert!(root.join(executable.strip_prefix("/").unwrap()).is_file());
+	}
+	// /bin/sh is on the machine running the tests, but not the device.
+	assert!(Path::new("/bin/sh").exists());
+	assert!(!daemons()
+		.iter()
+		.any(|(label, _)| label == "com.example.missing"));
+}
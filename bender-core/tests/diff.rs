@@ -0,0 +1,151 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that changes to keys bender doesn't know about still count as changes,
+// so that importing or restoring a configuration that only differs in them isn't skipped.
+
+use bender_core::{
+	diff::{diff, summarize, Change},
+	libhooker::{LibhookerConfig, Target, TweakConfig},
+};
+use plist::Value;
+
+fn safari() -> Target {
+	Target::App("com.apple.mobilesafari".to_string())
+}
+
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	config.tweak_configs.bundles.insert(
+		"com.apple.mobilesafari".to_string(),
+		TweakConfig {
+			custom_config: true,
+			..TweakConfig::default()
+		},
+	);
+	config
+}
+
+/// The target, key, old value, and new value of an unknown key that changed.
+type UnknownKey<'a> = (
+	Option<&'a Target>,
+	&'a str,
+	Option<&'a Value>,
+	Option<&'a Value>,
+);
+
+fn unknown_keys(changes: &[Change]) -> Vec<UnknownKey<'_>> {
+	changes
+		.iter()
+		.filter_map(|change| match change {
+			Change::UnknownKey {
+				target,
+				key,
+				old,
+				new,
+			} => Some((target.as_ref(), key.as_str(), old.as_ref(), new.as_ref())),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn unchanged_unknown_keys() {
+	let mut config = sample();
+	config
+		.extra
+		.insert("futureKey".to_string(), Value::Boolean(true));
+	assert!(diff(&config, &config.clone()).is_empty());
+}
+
+#[test]
+fn top_level_unknown_keys() {
+	let old = sample();
+	let mut new = old.clone();
+	new.extra
+		.insert("futureKey".to_string(), Value::String("hello".to_string()));
+	new.tweak_configs
+		.extra
+		.insert("futureTargetKind".to_string(), Value::Boolean(true));
+	let changes = diff(&old, &new);
+	assert_eq!(
+		unknown_keys(&changes),
+		vec![
+			(
+				None,
+				"tweakconfigs.futureTargetKind",
+				None,
+				Some(&Value::Boolean(true))
+			),
+			(
+				None,
+				"futureKey",
+				None,
+				Some(&Value::String("hello".to_string()))
+			),
+		]
+	);
+	assert_eq!(summarize(&changes), "2 changes to unknown keys");
+}
+
+#[test]
+fn target_unknown_keys() {
+	let mut old = sample();
+	let safari_config = safari().find_config_mut(&mut old.tweak_configs).unwrap();
+	safari_config
+		.extra
+		.insert("futureKey".to_string(), Value::Integer(1.into()));
+	safari_config
+		.extra
+		.insert("goneKey".to_string(), Value::Boolean(false));
+	let mut new = old.clone();
+	let safari_config = safari().find_config_mut(&mut new.tweak_configs).unwrap();
+	safari_config
+		.extra
+		.insert("futureKey".to_string(), Value::Integer(2.into()));
+	safari_config.extra.remove("goneKey");
+
+	let safari = safari();
+	let changes = diff(&old, &new);
+	assert_eq!(
+		unknown_keys(&changes),
+		vec![
+			(
+				Some(&safari),
+				"futureKey",
+				Some(&Value::Integer(1.into())),
+				Some(&Value::Integer(2.into()))
+			),
+			(Some(&safari), "goneKey", Some(&Value::Boolean(false)), None),
+		]
+	);
+	assert_eq!(summarize(&changes), "2 changes to com.apple.mobilesafari");
+}
+
+#[test]
+fn removed_targets_lose_their_unknown_keys() {
+	let mut old = sample();
+	safari()
+		.find_config_mut(&mut old.tweak_configs)
+		.unwrap()
+		.extra
+		.insert("futureKey".to_string(), Value::Boolean(true));
+	let mut new = old.clone();
+	safari().remove_config(&mut new.tweak_configs);
+	let safari = safari();
+	assert_eq!(
+		unknown_keys(&diff(&old, &new)),
+		vec![(
+			Some(&safari),
+			"futureKey",
+			Some(&Value::Boolean(true)),
+			None
+		)]
+	);
+}
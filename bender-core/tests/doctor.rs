@@ -0,0 +1,117 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender doctor` reads dpkg's status the way dpkg writes it,
+// and checks everything in the order that explains problems best.
+
+mod common;
+
+use bender_core::{
+	doctor::{self, installed_version, version_at_least, Status},
+	paths,
+};
+use common::write;
+use std::path::Path;
+
+/// A few packages, like dpkg writes them, with libhooker somewhere in the middle.
+const DPKG_STATUS: &str = "Package: bash
+Status: install ok installed
+Version: 5.1-1
+
+Package: org.coolstar.libhooker
+Status: install ok installed
+Priority: optional
+Version: 1.6.9-1
+Description: A hooking library
+
+Package: com.example.removed
+Status: deinstall ok config-files
+Version: 2.0
+
+Package: com.example.old
+Status: install ok installed
+Version: 0.9~beta2
+";
+
+#[test]
+fn installed_versions() {
+	assert_eq!(
+		installed_version(DPKG_STATUS, "org.coolstar.libhooker").as_deref(),
+		Some("1.6.9-1")
+	);
+	// The last paragraph doesn't have a blank line after it.
+	assert_eq!(
+		installed_version(DPKG_STATUS, "com.example.old").as_deref(),
+		Some("0.9~beta2")
+	);
+	// Removed, but not purged, isn't installed.
+	assert_eq!(installed_version(DPKG_STATUS, "com.example.removed"), None);
+	assert_eq!(installed_version(DPKG_STATUS, "com.example.missing"), None);
+	// A package's name showing up in another package's fields doesn't count either.
+	assert_eq!(installed_version(DPKG_STATUS, "libhooker"), None);
+	assert_eq!(installed_version("", "org.coolstar.libhooker"), None);
+}
+
+#[test]
+fn versions() {
+	assert!(version_at_least("1.4.0", "1.4.0"));
+	assert!(version_at_least("1.6.9-1", "1.4.0"));
+	assert!(version_at_least("1.10", "1.4.0"));
+	assert!(version_at_least("2", "1.4.0"));
+	assert!(!version_at_least("1.3.9", "1.4.0"));
+	assert!(!version_at_least("0.9~beta2", "1.4.0"));
+}
+
+/// A device with most of what bender needs, but no dpkg status and a broken plist.
+fn setup(root: &Path) {
+	write(root, paths::PROCURSUS_STRAPPED, "");
+	write(root, Path::new(paths::TWEAK_INJECT).join("Alpha.dylib"), "");
+	write(root, Path::new(paths::TWEAK_INJECT).join("Alpha.plist"), "");
+	write(root, paths::LIBHOOKER_PLIST, "not a plist");
+	write(root, "/usr/bin/launchctl", "");
+}
+
+#[test]
+fn checks() {
+	common::device("doctor", setup);
+	let checks = doctor::run();
+	assert_eq!(
+		checks.iter().map(|check| check.name).collect::<Vec<_>>(),
+		[
+			"bootstrap",
+			"libhooker",
+			"TweakInject",
+			"plist",
+			"plist writable",
+			"plist owner",
+			"launchctl",
+		]
+	);
+	let status = |name: &str| {
+		checks
+			.iter()
+			.find(|check| check.name == name)
+			.map(|check| check.status)
+			.unwrap()
+	};
+	assert_eq!(status("bootstrap"), Status::Ok);
+	assert_eq!(status("TweakInject"), Status::Ok);
+	assert_eq!(status("launchctl"), Status::Ok);
+	// dpkg's status file is missing, which isn't enough to stop bender.
+	let libhooker = doctor::libhooker_package();
+	assert_eq!(libhooker.status, Status::Warning);
+	assert!(libhooker
+		.detail
+		.starts_with(&format!("couldn't read {}", paths::DPKG_STATUS)));
+	assert!(libhooker.require().is_ok());
+	// A broken plist is, and the error says how to fix it.
+	assert_eq!(status("plist"), Status::Error);
+	let err = doctor::plist().require().unwrap_err().to_string();
+	assert!(err.contains("bender undo"));
+}
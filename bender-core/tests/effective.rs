@@ -0,0 +1,147 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender view --effective` shows what libhooker actually uses for a target,
+// including everything it falls back on from the default configuration.
+
+mod common;
+
+use bender_core::{
+	explain::{effective, Effective, Origin, Scope},
+	libhooker::{ConfigTypes, Target, TweakConfig, TweakMode},
+};
+use common::{tweaks, BACKBOARDD};
+use std::path::PathBuf;
+
+fn target_config(custom_config: bool, mode: TweakMode, entries: &[(&str, bool)]) -> TweakConfig {
+	TweakConfig {
+		custom_config,
+		allow_or_deny: mode,
+		tweak_configs: entries
+			.iter()
+			.map(|(tweak, value)| (tweak.to_string(), *value))
+			.collect(),
+		..TweakConfig::default()
+	}
+}
+
+fn sample() -> ConfigTypes {
+	let mut configs = ConfigTypes {
+		default: target_config(
+			false,
+			TweakMode::Deny,
+			&[("Alpha.dylib", true), ("Beta.dylib", false)],
+		),
+		..ConfigTypes::default()
+	};
+	configs.bundles.insert(
+		"com.apple.mobilesafari".to_string(),
+		target_config(
+			true,
+			TweakMode::Allow,
+			&[("Gamma.dylib", true), ("Gone.dylib", true)],
+		),
+	);
+	// These entries are ignored, since custom configuration is off.
+	configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		target_config(false, TweakMode::Allow, &[("Alpha.dylib", true)]),
+	);
+	configs.bundles.insert(
+		"com.example.foo".to_string(),
+		TweakConfig {
+			enable_tweaks: false,
+			..target_config(true, TweakMode::Deny, &[])
+		},
+	);
+	configs
+}
+
+/// Each tweak's name, entry, where that came from, and whether it loads.
+fn describe(effective: &Effective) -> Vec<(&str, Option<bool>, Origin, bool)> {
+	effective
+		.tweaks
+		.iter()
+		.map(|tweak| (tweak.tweak.as_str(), tweak.value, tweak.origin, tweak.loads))
+		.collect()
+}
+
+#[test]
+fn custom_configurations() {
+	let safari = effective(
+		&sample(),
+		&Target::App("com.apple.mobilesafari".to_string()),
+		&tweaks(),
+	);
+	assert_eq!(safari.scope, Scope::Target);
+	assert_eq!(safari.mode, TweakMode::Allow);
+	assert!(safari.enable_tweaks);
+	// Installed tweaks without an entry are listed too, and so are entries for tweaks that aren't installed.
+	assert_eq!(
+		describe(&safari),
+		[
+			("Alpha.dylib", None, Origin::Unset, false),
+			("Beta.dylib", None, Origin::Unset, false),
+			("Gamma.dylib", Some(true), Origin::Explicit, true),
+			("Gone.dylib", Some(true), Origin::Explicit, true),
+		]
+	);
+}
+
+#[test]
+fn falling_back_to_the_default() {
+	let configs = sample();
+	let inherited = [
+		("Alpha.dylib", Some(true), Origin::Inherited, false),
+		("Beta.dylib", Some(false), Origin::Inherited, true),
+		("Gamma.dylib", None, Origin::Unset, true),
+	];
+	// Whether the target has custom configuration off, or no configuration at all, doesn't matter.
+	for target in &[
+		Target::Executable(PathBuf::from(BACKBOARDD)),
+		Target::App("com.example.unconfigured".to_string()),
+	] {
+		let fallback = effective(&configs, target, &tweaks());
+		assert_eq!(fallback.scope, Scope::Default);
+		assert_eq!(fallback.mode, TweakMode::Deny);
+		assert_eq!(describe(&fallback), inherited, "{}", target.key());
+	}
+
+	// The default configuration's own entries aren't inherited from anywhere.
+	let default = effective(&configs, &Target::Default, &tweaks());
+	assert_eq!(default.tweaks[0].origin, Origin::Explicit);
+}
+
+#[test]
+fn tweak_loading_off() {
+	let mut configs = sample();
+	let foo = effective(
+		&configs,
+		&Target::App("com.example.foo".to_string()),
+		&tweaks(),
+	);
+	assert!(!foo.enable_tweaks);
+	assert!(foo.tweaks.iter().all(|tweak| !tweak.loads));
+
+	// Turning it off in the default configuration only matters to targets that use it.
+	configs.default.enable_tweaks = false;
+	let backboardd = effective(
+		&configs,
+		&Target::Executable(PathBuf::from(BACKBOARDD)),
+		&tweaks(),
+	);
+	assert!(!backboardd.enable_tweaks);
+	assert!(backboardd.tweaks.iter().all(|tweak| !tweak.loads));
+	let safari = effective(
+		&configs,
+		&Target::App("com.apple.mobilesafari".to_string()),
+		&tweaks(),
+	);
+	assert!(safari.enable_tweaks);
+}
@@ -0,0 +1,141 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that importing an exported configuration gives back exactly what was exported,
+// in every format, keys we don't know about included.
+
+mod common;
+
+use bender_core::{
+	diff::diff,
+	export::{export, import, Format},
+	libhooker::{LibhookerConfig, Target, TweakConfig, TweakMode, COMPAT_SUBSTRATE},
+};
+use common::{TempFile, BACKBOARDD};
+use plist::{Dictionary, Value};
+use std::path::PathBuf;
+
+const FORMATS: &[(Format, &str)] = &[
+	(Format::Json, "json"),
+	(Format::Yaml, "yaml"),
+	(Format::Toml, "toml"),
+	(Format::XmlPlist, "plist"),
+];
+
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig {
+		web_process_tweaks: false,
+		..LibhookerConfig::default()
+	};
+	config
+		.memory_compat_prefs
+		.insert("Alpha.dylib".to_string(), COMPAT_SUBSTRATE);
+	let configs = &mut config.tweak_configs;
+	configs.default.enable_tweaks = false;
+	let mut safari = TweakConfig {
+		custom_config: true,
+		allow_or_deny: TweakMode::Allow,
+		..TweakConfig::default()
+	};
+	safari.set_tweak_entry("Alpha.dylib", Some(true));
+	safari.set_tweak_entry("Beta.dylib", Some(false));
+	safari
+		.extra
+		.insert("futureKey".to_string(), Value::Real(1.5));
+	configs
+		.bundles
+		.insert("com.apple.mobilesafari".to_string(), safari);
+	configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		TweakConfig {
+			custom_config: true,
+			enable_tweaks: false,
+			..TweakConfig::default()
+		},
+	);
+
+	// Keys we don't know about, with everything the text formats can hold.
+	let mut nested = Dictionary::new();
+	nested.insert("number".to_string(), Value::Integer(42.into()));
+	nested.insert(
+		"list".to_string(),
+		Value::Array(vec![Value::Boolean(true), Value::String("two".to_string())]),
+	);
+	config
+		.extra
+		.insert("futureTopLevelKey".to_string(), Value::Dictionary(nested));
+	configs
+		.extra
+		.insert("futureTargetKind".to_string(), Value::Boolean(true));
+	config
+}
+
+fn roundtrip(config: &LibhookerConfig, format: Format, extension: &str) -> LibhookerConfig {
+	let file = TempFile::new(
+		&format!("export.{}", extension),
+		&export(config, format).unwrap(),
+	);
+	import(&file.0).unwrap()
+}
+
+/// A configuration as a plain plist value, so every key can be compared, not just what `diff` looks at.
+fn to_value(config: &LibhookerConfig) -> Value {
+	let mut bytes = Vec::new();
+	plist::to_writer_binary(&mut bytes, config).unwrap();
+	Value::from_reader(std::io::Cursor::new(bytes)).unwrap()
+}
+
+#[test]
+fn every_format_roundtrips() {
+	let config = sample();
+	for (format, extension) in FORMATS {
+		let imported = roundtrip(&config, *format, extension);
+		assert!(diff(&config, &imported).is_empty(), "{:?}", format);
+		assert_eq!(to_value(&config), to_value(&imported), "{:?}", format);
+	}
+}
+
+#[test]
+fn plist_only_values() {
+	let mut config = sample();
+	Target::App("com.apple.mobilesafari".to_string())
+		.find_config_mut(&mut config.tweak_configs)
+		.unwrap()
+		.extra
+		.insert("futureData".to_string(), Value::Data(vec![0xde, 0xad]));
+
+	// Exporting that as anything but a plist would turn it into something else, so it's refused...
+	for (format, _) in &FORMATS[..3] {
+		let err = export(&config, *format).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			format!(
+				"tweakconfigs.bundles.com.apple.mobilesafari.futureData holds data or a date, \
+				 which {} can't hold, export as xml-plist instead",
+				format.name()
+			)
+		);
+	}
+	// ...but a plist keeps it just like it was.
+	let imported = roundtrip(&config, Format::XmlPlist, "plist");
+	assert_eq!(to_value(&config), to_value(&imported));
+}
+
+#[test]
+fn unknown_extensions() {
+	let file = TempFile::new("export.txt", "");
+	let err = import(&file.0).unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		format!(
+			"{} should end in .json, .yaml, .yml, .toml, or .plist",
+			file.0.display()
+		)
+	);
+}
@@ -0,0 +1,133 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that tweak filters are read the way tweaks actually write them,
+// in both XML and old-style (OpenStep) plists, and matched the way libhooker matches them.
+
+mod common;
+
+use bender_core::filter::{Process, TweakFilter};
+use common::TempFile;
+
+fn read(name: &str, contents: &str) -> TweakFilter {
+	let file = TempFile::new(&format!("{}.plist", name), contents);
+	TweakFilter::from_file(&file.0).expect("failed to read filter")
+}
+
+fn safari() -> Process {
+	Process {
+		bundle_id: Some("com.apple.mobilesafari".to_string()),
+		executable: Some("MobileSafari".to_string()),
+		has_ui: true,
+	}
+}
+
+fn backboardd() -> Process {
+	Process {
+		bundle_id: None,
+		executable: Some("backboardd".to_string()),
+		has_ui: false,
+	}
+}
+
+#[test]
+fn xml_filters() {
+	let filter = read(
+		"xml",
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Filter</key>
+	<dict>
+		<key>Bundles</key>
+		<array><string>com.apple.mobilesafari</string></array>
+		<key>Executables</key>
+		<array><string>backboardd</string></array>
+		<key>CoreFoundationVersion</key>
+		<array><real>1240.1</real><integer>2000</integer></array>
+	</dict>
+</dict>
+</plist>"#,
+	);
+	assert_eq!(filter.bundles, ["com.apple.mobilesafari"]);
+	assert_eq!(filter.executables, ["backboardd"]);
+	assert_eq!(filter.core_foundation_version, [1240.1, 2000.0]);
+	// Without a mode, everything has to match.
+	assert!(!filter.any);
+	assert!(!filter.matches(&safari()));
+	assert!(!filter.matches(&backboardd()));
+}
+
+#[test]
+fn any_mode() {
+	let filter = read(
+		"any",
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Filter</key>
+	<dict>
+		<key>Bundles</key>
+		<array><string>com.apple.mobilesafari</string></array>
+		<key>Executables</key>
+		<array><string>backboardd</string></array>
+		<key>Mode</key>
+		<string>Any</string>
+	</dict>
+</dict>
+</plist>"#,
+	);
+	assert!(filter.any);
+	assert!(filter.matches(&safari()));
+	assert!(filter.matches(&backboardd()));
+	assert_eq!(
+		filter.to_string(),
+		"bundles com.apple.mobilesafari, or executables backboardd"
+	);
+}
+
+#[test]
+fn old_style_filters() {
+	// Plenty of tweaks still ship OpenStep plists, with every number written as a string.
+	let filter = read(
+		"openstep",
+		r#"{ Filter = { Bundles = ( "com.apple.UIKit" ); CoreFoundationVersion = ( "1240.10" ); }; }"#,
+	);
+	assert_eq!(filter.bundles, ["com.apple.UIKit"]);
+	assert_eq!(filter.core_foundation_version, [1240.1]);
+	assert!(!filter.any);
+	// UIKit is loaded into everything with a UI.
+	assert!(filter.matches(&safari()));
+	assert!(!filter.matches(&backboardd()));
+}
+
+#[test]
+fn old_style_any_mode() {
+	let filter = read(
+		"openstep-any",
+		r#"{ Filter = { Executables = ( "backboardd" ); Bundles = ( "com.apple.mobilesafari" ); Mode = "Any"; }; }"#,
+	);
+	assert!(filter.any);
+	assert!(filter.matches(&safari()));
+	assert!(filter.matches(&backboardd()));
+}
+
+#[test]
+fn empty_filters() {
+	// A filter that doesn't name anything is injected everywhere.
+	let filter = read("empty", "{ Filter = { }; }");
+	assert!(filter.is_everywhere());
+	assert!(filter.matches(&backboardd()));
+	assert_eq!(filter.to_string(), "everything");
+
+	let broken = TempFile::new("broken.plist", "{ Filter = ");
+	assert!(TweakFilter::from_file(&broken.0).is_none());
+	assert!(TweakFilter::from_file("/nonexistent/Filter.plist").is_none());
+}
@@ -0,0 +1,244 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that compacting a configuration never changes which tweaks load where,
+// and never throws away anything that could matter later.
+
+use bender_core::{
+	explain::explain,
+	gc::{compact, is_redundant},
+	libhooker::{
+		LibhookerConfig, Target, TweakConfig, TweakMode, COMPAT_LIBHOOKER, COMPAT_SUBSTRATE,
+	},
+};
+use plist::Value;
+use std::path::PathBuf;
+
+const TWEAKS: &[&str] = &["Alpha.dylib", "Beta.dylib", "Gamma.dylib"];
+
+fn target_config(
+	custom_config: bool,
+	enable_tweaks: bool,
+	mode: TweakMode,
+	entries: &[(&str, bool)],
+) -> TweakConfig {
+	TweakConfig {
+		custom_config,
+		enable_tweaks,
+		allow_or_deny: mode,
+		tweak_configs: entries
+			.iter()
+			.map(|(tweak, value)| (tweak.to_string(), *value))
+			.collect(),
+		..TweakConfig::default()
+	}
+}
+
+fn app(bundle: &str) -> Target {
+	Target::App(bundle.to_string())
+}
+
+/// A configuration with a little bit of everything compaction cares about.
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	let configs = &mut config.tweak_configs;
+	configs.default = target_config(
+		false,
+		true,
+		TweakMode::Deny,
+		&[("Alpha.dylib", true), ("Beta.dylib", false)],
+	);
+	let targets = vec![
+		// Allowlist with a pointless "off" entry.
+		(
+			"com.example.allow",
+			target_config(
+				true,
+				true,
+				TweakMode::Allow,
+				&[("Alpha.dylib", false), ("Beta.dylib", true)],
+			),
+		),
+		// Exactly like the default configuration, once the "off" entry is gone.
+		(
+			"com.example.same",
+			target_config(
+				true,
+				true,
+				TweakMode::Deny,
+				&[("Alpha.dylib", true), ("Gamma.dylib", false)],
+			),
+		),
+		// Custom configuration off, but with entries that come back when it's turned on.
+		(
+			"com.example.dormant",
+			target_config(
+				false,
+				true,
+				TweakMode::Allow,
+				&[("Alpha.dylib", true), ("Beta.dylib", false)],
+			),
+		),
+		// Custom configuration off, and nothing else.
+		(
+			"com.example.empty",
+			target_config(false, true, TweakMode::Deny, &[("Beta.dylib", false)]),
+		),
+		// Tweak loading off entirely.
+		(
+			"com.example.disabled",
+			target_config(false, false, TweakMode::Deny, &[]),
+		),
+	];
+	for (bundle, tweak_config) in targets {
+		configs.bundles.insert(bundle.to_string(), tweak_config);
+	}
+	configs.paths.insert(
+		PathBuf::from("/usr/libexec/backboardd"),
+		target_config(true, true, TweakMode::Deny, &[("Gamma.dylib", true)]),
+	);
+	config
+		.memory_compat_prefs
+		.insert("Alpha.dylib".to_string(), COMPAT_SUBSTRATE);
+	config
+		.memory_compat_prefs
+		.insert("Beta.dylib".to_string(), COMPAT_LIBHOOKER);
+	config
+}
+
+fn every_target(config: &LibhookerConfig) -> Vec<Target> {
+	let configs = &config.tweak_configs;
+	std::iter::once(Target::Default)
+		.chain(
+			configs
+				.paths
+				.keys()
+				.map(|path| Target::Executable(path.clone())),
+		)
+		.chain(configs.bundles.keys().map(|bundle| app(bundle)))
+		.collect()
+}
+
+#[test]
+fn compacting_keeps_every_decision() {
+	let before = sample();
+	let mut after = before.clone();
+	compact(&mut after);
+	for target in every_target(&before) {
+		for tweak in TWEAKS {
+			assert_eq!(
+				explain(&before.tweak_configs, &target, None, tweak, None).loads,
+				explain(&after.tweak_configs, &target, None, tweak, None).loads,
+				"{} in {:?}",
+				tweak,
+				target
+			);
+		}
+	}
+}
+
+#[test]
+fn off_entries_are_dropped() {
+	let mut config = sample();
+	compact(&mut config);
+	let configs = &config.tweak_configs;
+	assert!(configs.default.tweak_entry("Beta.dylib").is_none());
+	assert_eq!(configs.default.tweak_entry("Alpha.dylib"), Some(true));
+	let allow = app("com.example.allow").find_config(configs).unwrap();
+	assert!(allow.tweak_entry("Alpha.dylib").is_none());
+	assert_eq!(allow.tweak_entry("Beta.dylib"), Some(true));
+}
+
+#[test]
+fn dormant_entries_are_kept() {
+	let mut config = sample();
+	compact(&mut config);
+	let configs = &config.tweak_configs;
+	let dormant = app("com.example.dormant").find_config(configs).unwrap();
+	assert_eq!(dormant.tweak_entry("Alpha.dylib"), Some(true));
+	assert!(dormant.tweak_entry("Beta.dylib").is_none());
+	assert_eq!(dormant.allow_or_deny, TweakMode::Allow);
+}
+
+#[test]
+fn redundant_targets_are_removed() {
+	let mut config = sample();
+	compact(&mut config);
+	let configs = &config.tweak_configs;
+	assert!(app("com.example.same").find_config(configs).is_none());
+	assert!(app("com.example.empty").find_config(configs).is_none());
+	assert!(app("com.example.allow").find_config(configs).is_some());
+	assert!(app("com.example.disabled").find_config(configs).is_some());
+	assert!(Target::Executable(PathBuf::from("/usr/libexec/backboardd"))
+		.find_config(configs)
+		.is_some());
+}
+
+#[test]
+fn default_compat_modes_are_dropped() {
+	let mut config = sample();
+	compact(&mut config);
+	assert_eq!(
+		config.memory_compat_prefs.get("Alpha.dylib"),
+		Some(&COMPAT_SUBSTRATE)
+	);
+	assert!(!config.memory_compat_prefs.contains_key("Beta.dylib"));
+}
+
+#[test]
+fn compacting_twice_changes_nothing() {
+	let mut once = sample();
+	compact(&mut once);
+	let mut twice = once.clone();
+	compact(&mut twice);
+	assert!(bender_core::diff::diff(&once, &twice).is_empty());
+}
+
+#[test]
+fn redundancy() {
+	let default = target_config(false, true, TweakMode::Deny, &[("Alpha.dylib", true)]);
+	let same = target_config(true, true, TweakMode::Deny, &[("Alpha.dylib", true)]);
+	assert!(is_redundant(&same, &default));
+	assert!(is_redundant(
+		&target_config(false, true, TweakMode::Allow, &[]),
+		&default
+	));
+
+	// Anything that changes a decision, or might later, isn't.
+	assert!(!is_redundant(
+		&target_config(true, true, TweakMode::Allow, &[("Alpha.dylib", true)]),
+		&default
+	));
+	assert!(!is_redundant(
+		&target_config(true, true, TweakMode::Deny, &[]),
+		&default
+	));
+	assert!(!is_redundant(
+		&target_config(false, false, TweakMode::Deny, &[]),
+		&default
+	));
+	assert!(!is_redundant(
+		&target_config(false, true, TweakMode::Deny, &[("Alpha.dylib", true)]),
+		&default
+	));
+
+	// A custom configuration ignores the default configuration turning tweak loading off.
+	let disabled_default = TweakConfig {
+		enable_tweaks: false,
+		..default.clone()
+	};
+	assert!(!is_redundant(&same, &disabled_default));
+
+	// And nobody knows what unknown keys do.
+	let mut unknown = same;
+	unknown
+		.extra
+		.insert("futureKey".to_string(), Value::Boolean(true));
+	assert!(!is_redundant(&unknown, &default));
+}
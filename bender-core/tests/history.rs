@@ -0,0 +1,124 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that snapshots are kept in order, pruned from the oldest end,
+// and can always be put back, even over a configuration that's broken.
+
+mod common;
+
+use bender_core::{
+	history::{History, Snapshot, HISTORY_LIMIT},
+	libhooker::LibhookerConfig,
+	store::ConfigFile,
+};
+use common::TempDir;
+
+fn ids(snapshots: &[Snapshot]) -> Vec<String> {
+	snapshots
+		.iter()
+		.map(|snapshot| snapshot.id.clone())
+		.collect()
+}
+
+/// Record a bunch of snapshots, returning their IDs newest first, just like [History::snapshots].
+fn record(history: &History, count: usize) -> Vec<String> {
+	let mut recorded = (0..count)
+		.map(|n| {
+			history
+				.record(format!("plist {}", n).as_bytes(), &format!("change {}", n))
+				.unwrap()
+				.id
+		})
+		.collect::<Vec<String>>();
+	recorded.reverse();
+	recorded
+}
+
+#[test]
+fn snapshots_are_in_order() {
+	let dir = TempDir::new("history-order");
+	let history = History::new(&dir.0);
+	assert!(history.snapshots().unwrap().is_empty());
+	assert!(history.latest().unwrap().is_none());
+
+	// Plenty of these land in the same second, which have to sort right too.
+	let recorded = record(&history, 12);
+	assert_eq!(ids(&history.snapshots().unwrap()), recorded);
+	let latest = history.latest().unwrap().unwrap();
+	assert_eq!(latest.id, recorded[0]);
+	assert_eq!(latest.summary, "change 11");
+	assert_eq!(latest.bytes().unwrap(), b"plist 11");
+}
+
+#[test]
+fn old_snapshots_are_pruned() {
+	let dir = TempDir::new("history-pruned");
+	let history = History::new(&dir.0);
+	let recorded = record(&history, HISTORY_LIMIT + 5);
+	assert_eq!(
+		ids(&history.snapshots().unwrap()),
+		recorded[..HISTORY_LIMIT]
+	);
+	// Summaries go along with their snapshots.
+	assert_eq!(
+		std::fs::read_dir(&dir.0).unwrap().count(),
+		HISTORY_LIMIT * 2
+	);
+}
+
+#[test]
+fn finding_snapshots() {
+	let dir = TempDir::new("history-find");
+	let history = History::new(&dir.0);
+	let recorded = record(&history, 2);
+	let find = |id: &str| history.find(id).unwrap().map(|snapshot| snapshot.id);
+	assert_eq!(find(&recorded[1]), Some(recorded[1].clone()));
+	assert_eq!(
+		find(&format!(" {} ", recorded[0])),
+		Some(recorded[0].clone())
+	);
+	// The start of an ID is enough, unless it could be more than one snapshot.
+	assert_eq!(find("2"), None);
+	assert_eq!(find("nonexistent"), None);
+
+	let summary = history.record(b"plist", "first line\nsecond line").unwrap();
+	assert_eq!(summary.summary, "first line");
+	assert!(summary.date().ends_with(" UTC"));
+}
+
+#[test]
+fn restoring_over_a_broken_plist() {
+	let dir = TempDir::new("history-restore");
+	let history = History::new(dir.0.join("history"));
+	let path = dir.0.join("org.coolstar.libhooker.plist");
+	let mut config_file = ConfigFile::new(&path).with_history(history.clone());
+	config_file
+		.save(&LibhookerConfig::default(), Some("first"))
+		.unwrap();
+	let good = std::fs::read(&path).unwrap();
+
+	// Something mangles the configuration, which bender can't read anymore...
+	std::fs::write(&path, "not a plist").unwrap();
+	let mut config_file = ConfigFile::new(&path).with_history(history.clone());
+	assert!(config_file.load().is_err());
+
+	// ...but it can still put a snapshot back over it, byte for byte.
+	let snapshot = history.latest().unwrap().unwrap();
+	assert_eq!(snapshot.summary, "first");
+	config_file.load_raw().unwrap();
+	config_file.save_raw(&good, Some("restored")).unwrap();
+	assert_eq!(std::fs::read(&path).unwrap(), good);
+	assert!(ConfigFile::new(&path).load().is_ok());
+
+	// And the broken configuration gets a snapshot too, in case it mattered.
+	let broken = history.latest().unwrap().unwrap();
+	assert_eq!(broken.summary, "restored");
+	assert_eq!(broken.bytes().unwrap(), b"not a plist");
+	assert!(broken.load().is_err());
+}
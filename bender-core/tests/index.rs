@@ -0,0 +1,121 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender where` finds every target a tweak is configured in,
+// and says what each of those entries actually does.
+
+mod common;
+
+use bender_core::{
+	index::TweakIndex,
+	libhooker::{ConfigTypes, Target, TweakConfig, TweakMode},
+	tweaks::fix_tweak_name,
+};
+use common::BACKBOARDD;
+use std::path::PathBuf;
+
+fn target_config(custom_config: bool, mode: TweakMode, entries: &[(&str, bool)]) -> TweakConfig {
+	TweakConfig {
+		custom_config,
+		allow_or_deny: mode,
+		tweak_configs: entries
+			.iter()
+			.map(|(tweak, value)| (tweak.to_string(), *value))
+			.collect(),
+		..TweakConfig::default()
+	}
+}
+
+fn sample() -> ConfigTypes {
+	let mut configs = ConfigTypes {
+		default: target_config(false, TweakMode::Deny, &[("Alpha.dylib", true)]),
+		..ConfigTypes::default()
+	};
+	configs.bundles.insert(
+		"com.apple.mobilesafari".to_string(),
+		target_config(
+			true,
+			TweakMode::Allow,
+			&[("Alpha.dylib", true), ("Gone.dylib", false)],
+		),
+	);
+	configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		target_config(false, TweakMode::Allow, &[("Alpha.dylib", true)]),
+	);
+	configs
+}
+
+#[test]
+fn every_entry_for_a_tweak() {
+	let index = TweakIndex::build(&sample());
+	let entries = index
+		.get("Alpha.dylib")
+		.iter()
+		.map(|entry| {
+			(
+				entry.target.clone(),
+				entry.value,
+				entry.mode,
+				entry.allows,
+				entry.ignored,
+				entry.loads,
+			)
+		})
+		.collect::<Vec<_>>();
+	// The default configuration comes first, then executables, then apps.
+	assert_eq!(
+		entries,
+		[
+			(Target::Default, true, TweakMode::Deny, false, false, false),
+			// backboardd's entry would allow it, but it's ignored, so the default configuration's entry denies it.
+			(
+				Target::Executable(PathBuf::from(BACKBOARDD)),
+				true,
+				TweakMode::Allow,
+				true,
+				true,
+				false,
+			),
+			(
+				Target::App("com.apple.mobilesafari".to_string()),
+				true,
+				TweakMode::Allow,
+				true,
+				false,
+				true,
+			),
+		]
+	);
+	assert_eq!(
+		index.tweaks().collect::<Vec<_>>(),
+		["Alpha.dylib", "Gone.dylib"]
+	);
+}
+
+#[test]
+fn tweaks_without_entries() {
+	let index = TweakIndex::build(&sample());
+	assert!(index.get("Beta.dylib").is_empty());
+	assert!(TweakIndex::build(&ConfigTypes::default())
+		.tweaks()
+		.next()
+		.is_none());
+
+	// A tweak that isn't installed anymore can still be found by the tweaks that are configured.
+	let configured = index.tweaks().cloned().collect::<Vec<String>>();
+	assert_eq!(
+		fix_tweak_name(&configured, "gone").unwrap().as_deref(),
+		Some("Gone.dylib")
+	);
+	assert_eq!(fix_tweak_name(&configured, "Missing").unwrap(), None);
+	let gone = index.get("Gone.dylib");
+	assert_eq!(gone.len(), 1);
+	assert!(!gone[0].loads);
+}
@@ -0,0 +1,185 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender lint` finds every kind of problem,
+// and that `--fix` gets rid of everything it can without touching anything else.
+
+mod common;
+
+use bender_core::{
+	apps::App,
+	libhooker::{LibhookerConfig, Target, TweakConfig, COMPAT_SUBSTRATE},
+	lint::{lint, Issue, Severity},
+};
+use common::{apps, daemons, tweaks, BACKBOARDD, SAFARI};
+use std::path::PathBuf;
+
+const MISSING: &str = "/nonexistent/bender/missing";
+
+fn tweak_config(custom_config: bool, entries: &[(&str, bool)]) -> TweakConfig {
+	TweakConfig {
+		custom_config,
+		tweak_configs: entries
+			.iter()
+			.map(|(tweak, value)| (tweak.to_string(), *value))
+			.collect(),
+		..TweakConfig::default()
+	}
+}
+
+/// A configuration with one of every issue, and plenty that's fine.
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	let configs = &mut config.tweak_configs;
+	configs.default = tweak_config(false, &[("Alpha.dylib", true)]);
+	// Fine on it's own, but Safari is configured by it's executable too.
+	configs.bundles.insert(
+		"com.apple.mobilesafari".to_string(),
+		tweak_config(true, &[("Alpha.dylib", true)]),
+	);
+	configs.paths.insert(
+		PathBuf::from(SAFARI),
+		tweak_config(true, &[("Beta.dylib", true)]),
+	);
+	// An uninstalled tweak, next to an installed one.
+	configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		tweak_config(true, &[("Alpha.dylib", true), ("Gone.dylib", true)]),
+	);
+	configs.paths.insert(
+		PathBuf::from(MISSING),
+		tweak_config(true, &[("Alpha.dylib", true)]),
+	);
+	configs.bundles.insert(
+		"com.example.gone".to_string(),
+		tweak_config(true, &[("Alpha.dylib", true)]),
+	);
+	// Entries that libhooker ignores.
+	configs.bundles.insert(
+		"com.example.ignored".to_string(),
+		tweak_config(false, &[("Alpha.dylib", true), ("Beta.dylib", false)]),
+	);
+	config
+		.memory_compat_prefs
+		.insert("Alpha.dylib".to_string(), COMPAT_SUBSTRATE);
+	config
+		.memory_compat_prefs
+		.insert("Gone.dylib".to_string(), COMPAT_SUBSTRATE);
+	config
+}
+
+fn lint_sample(config: &LibhookerConfig) -> Vec<Issue> {
+	// Pretend that the ignored app is installed.
+	let mut apps = apps();
+	apps.push(App {
+		identifier: "com.example.ignored".to_string(),
+		name: "Ignored".to_string(),
+		executable: None,
+		version: None,
+	});
+	lint(config, &tweaks(), &apps, &daemons()).unwrap()
+}
+
+fn app(bundle: &str) -> Target {
+	Target::App(bundle.to_string())
+}
+
+fn executable(path: &str) -> Target {
+	Target::Executable(PathBuf::from(path))
+}
+
+/// Describe an issue by it's kind and what it's about, so they can be compared.
+fn describe(issue: &Issue) -> String {
+	match issue {
+		Issue::UninstalledTweak { target, tweak } => {
+			format!("uninstalled tweak {} in {}", tweak, target.key())
+		}
+		Issue::MissingApp { target } => format!("missing app {}", target.key()),
+		Issue::MissingExecutable { target } => format!("missing executable {}", target.key()),
+		Issue::DuplicateTarget { app, executable } => {
+			format!("duplicate {} and {}", app.key(), executable.key())
+		}
+		Issue::IgnoredTweaks { target, count } => {
+			format!("{} ignored tweaks in {}", count, target.key())
+		}
+		Issue::UninstalledCompat { tweak } => format!("uninstalled compat {}", tweak),
+	}
+}
+
+#[test]
+fn every_issue() {
+	let issues = lint_sample(&sample());
+	assert_eq!(
+		issues.iter().map(describe).collect::<Vec<String>>(),
+		vec![
+			format!("duplicate com.apple.mobilesafari and {}", SAFARI),
+			format!("missing executable {}", MISSING),
+			format!("uninstalled tweak Gone.dylib in {}", BACKBOARDD),
+			"missing app com.example.gone".to_string(),
+			"uninstalled compat Gone.dylib".to_string(),
+			"2 ignored tweaks in com.example.ignored".to_string(),
+		]
+	);
+	// The most serious issues come first.
+	let severities = issues.iter().map(Issue::severity).collect::<Vec<_>>();
+	assert_eq!(
+		severities,
+		vec![
+			Severity::Error,
+			Severity::Warning,
+			Severity::Warning,
+			Severity::Warning,
+			Severity::Warning,
+			Severity::Info,
+		]
+	);
+}
+
+#[test]
+fn fixing() {
+	let mut config = sample();
+	for issue in lint_sample(&config) {
+		assert_eq!(issue.fix(&mut config), issue.fixable());
+	}
+
+	// Only what can't be fixed is left.
+	assert_eq!(
+		lint_sample(&config)
+			.iter()
+			.map(describe)
+			.collect::<Vec<String>>(),
+		vec![format!("duplicate com.apple.mobilesafari and {}", SAFARI)]
+	);
+
+	let configs = &config.tweak_configs;
+	assert!(executable(MISSING).find_config(configs).is_none());
+	assert!(app("com.example.gone").find_config(configs).is_none());
+	let backboardd = executable(BACKBOARDD).find_config(configs).unwrap();
+	assert_eq!(backboardd.tweak_entry("Alpha.dylib"), Some(true));
+	assert!(backboardd.tweak_entry("Gone.dylib").is_none());
+	let ignored = app("com.example.ignored").find_config(configs).unwrap();
+	assert!(ignored.tweak_configs.is_empty());
+	assert!(!config.memory_compat_prefs.contains_key("Gone.dylib"));
+	assert!(config.memory_compat_prefs.contains_key("Alpha.dylib"));
+
+	// Both of the duplicate configurations are left alone.
+	assert!(app("com.apple.mobilesafari").find_config(configs).is_some());
+	assert!(executable(SAFARI).find_config(configs).is_some());
+	assert_eq!(configs.default.tweak_entry("Alpha.dylib"), Some(true));
+}
+
+#[test]
+fn nothing_to_lint() {
+	let mut config = LibhookerConfig::default();
+	config.tweak_configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		tweak_config(true, &[("Alpha.dylib", true)]),
+	);
+	assert!(lint_sample(&config).is_empty());
+}
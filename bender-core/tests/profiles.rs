@@ -0,0 +1,132 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that profiles are saved, switched to, and deleted by name,
+// and that a name can never point outside of the profiles directory.
+
+mod common;
+
+use bender_core::{
+	diff::diff,
+	libhooker::{LibhookerConfig, Target, TweakConfig, COMPAT_SUBSTRATE},
+	profiles::{Profile, Profiles},
+};
+use common::TempDir;
+use plist::Value;
+
+fn sample() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	config
+		.memory_compat_prefs
+		.insert("Alpha.dylib".to_string(), COMPAT_SUBSTRATE);
+	let mut safari = TweakConfig {
+		custom_config: true,
+		..TweakConfig::default()
+	};
+	safari.set_tweak_entry("Beta.dylib", Some(false));
+	Target::App("com.apple.mobilesafari".to_string())
+		.apply_config(&mut config.tweak_configs, safari);
+	config
+}
+
+#[test]
+fn saving_and_listing() {
+	let dir = TempDir::new("profiles-saving");
+	let profiles = Profiles::new(dir.0.join("profiles"));
+	// Nothing has been saved yet, so there's not even a directory.
+	assert!(profiles.list().unwrap().is_empty());
+	assert!(profiles.load("work").unwrap().is_none());
+
+	let config = sample();
+	profiles
+		.save("work", &Profile::from_config(&config))
+		.unwrap();
+	profiles
+		.save(" play ", &Profile::from_config(&LibhookerConfig::default()))
+		.unwrap();
+	dir.write("profiles/notes.txt", "not a profile");
+	assert_eq!(profiles.list().unwrap(), ["play", "work"]);
+
+	let mut loaded = LibhookerConfig::default();
+	profiles
+		.load("work")
+		.unwrap()
+		.unwrap()
+		.apply_to(&mut loaded);
+	assert!(diff(&config, &loaded).is_empty());
+
+	// Saving under the same name replaces it.
+	profiles
+		.save("work", &Profile::from_config(&LibhookerConfig::default()))
+		.unwrap();
+	let replaced = profiles.load("work").unwrap().unwrap();
+	assert!(replaced.tweak_configs.bundles.is_empty());
+	assert_eq!(profiles.list().unwrap(), ["play", "work"]);
+
+	dir.write("profiles/broken.plist", "not a plist");
+	assert!(profiles.load("broken").is_err());
+}
+
+#[test]
+fn applying() {
+	let profile = Profile::from_config(&sample());
+	let mut config = LibhookerConfig {
+		web_process_tweaks: false,
+		..LibhookerConfig::default()
+	};
+	config
+		.extra
+		.insert("futureKey".to_string(), Value::Boolean(true));
+	config
+		.memory_compat_prefs
+		.insert("Gamma.dylib".to_string(), COMPAT_SUBSTRATE);
+	profile.apply_to(&mut config);
+
+	// The tweak configurations and compatibility modes are replaced outright...
+	assert!(Target::App("com.apple.mobilesafari".to_string())
+		.find_config(&config.tweak_configs)
+		.is_some());
+	assert_eq!(
+		config.memory_compat_prefs.keys().collect::<Vec<_>>(),
+		["Alpha.dylib"]
+	);
+	// ...but nothing else is a part of a profile.
+	assert!(!config.web_process_tweaks);
+	assert!(config.extra.contains_key("futureKey"));
+}
+
+#[test]
+fn deleting() {
+	let dir = TempDir::new("profiles-deleting");
+	let profiles = Profiles::new(&dir.0);
+	profiles
+		.save("work", &Profile::from_config(&sample()))
+		.unwrap();
+	assert!(profiles.delete("work").unwrap());
+	assert!(!profiles.delete("work").unwrap());
+	assert!(profiles.list().unwrap().is_empty());
+}
+
+#[test]
+fn bad_names() {
+	let dir = TempDir::new("profiles-names");
+	let profiles = Profiles::new(dir.0.join("profiles"));
+	let profile = Profile::from_config(&sample());
+	for name in &["", "  ", ".hidden", "..", "../escape", "nested/name"] {
+		let expected = format!("'{}' is not a valid profile name", name.trim());
+		assert_eq!(
+			profiles.save(name, &profile).unwrap_err().to_string(),
+			expected
+		);
+		assert_eq!(profiles.load(name).unwrap_err().to_string(), expected);
+		assert_eq!(profiles.delete(name).unwrap_err().to_string(), expected);
+	}
+	// Nothing was written anywhere, in the profiles directory or outside of it.
+	assert!(std::fs::read_dir(&dir.0).unwrap().next().is_none());
+}
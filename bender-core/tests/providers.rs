@@ -0,0 +1,155 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that bender figures out which tweak, app, or daemon people meant,
+// using plain lists as the providers, just like a manifest does.
+
+mod common;
+
+use bender_core::{
+	apps::{fix_app_name, get_friendly_app_name},
+	libhooker::{Target, SPRINGBOARD},
+	manifest::Manifest,
+	tweaks::{fix_tweak_name, TweakSource},
+};
+use common::{apps, daemons, tweaks, TempFile, BACKBOARDD};
+use std::path::PathBuf;
+
+fn resolve(target: &str) -> Option<Target> {
+	Target::resolve(target, &apps(), &daemons()).unwrap()
+}
+
+#[test]
+fn tweak_names() {
+	let tweaks = tweaks();
+	let fix = |input| fix_tweak_name(&tweaks, input).unwrap();
+	assert_eq!(fix("Alpha.dylib").as_deref(), Some("Alpha.dylib"));
+	assert_eq!(fix("Alpha").as_deref(), Some("Alpha.dylib"));
+	assert_eq!(fix(" beta ").as_deref(), Some("Beta.dylib"));
+	assert_eq!(fix("GAMMA.DYLIB").as_deref(), Some("Gamma.dylib"));
+	assert_eq!(fix("Delta"), None);
+	assert_eq!(fix("Gam"), None);
+}
+
+#[test]
+fn app_names() {
+	let apps = apps();
+	let fix = |input| fix_app_name(&apps, input).unwrap();
+	assert_eq!(
+		fix("com.apple.mobilesafari").as_deref(),
+		Some("com.apple.mobilesafari")
+	);
+	assert_eq!(fix("safari").as_deref(), Some("com.apple.mobilesafari"));
+	assert_eq!(
+		fix("MobileSafari").as_deref(),
+		Some("com.apple.mobilesafari")
+	);
+	assert_eq!(fix("fooapp").as_deref(), Some("com.example.foo"));
+	assert_eq!(fix("Foo App").as_deref(), Some("com.example.foo"));
+	assert_eq!(fix("Chrome"), None);
+
+	assert_eq!(get_friendly_app_name(&apps, "com.example.foo"), "Foo App");
+	assert_eq!(
+		get_friendly_app_name(&apps, "com.example.gone"),
+		"com.example.gone"
+	);
+}
+
+#[test]
+fn targets() {
+	assert_eq!(resolve("default"), Some(Target::Default));
+	assert_eq!(
+		resolve("SpringBoard"),
+		Some(Target::Executable(PathBuf::from(SPRINGBOARD)))
+	);
+	assert_eq!(
+		resolve("Safari"),
+		Some(Target::App("com.apple.mobilesafari".to_string()))
+	);
+
+	// Daemons can be found by their name, label, or path.
+	let backboardd = Some(Target::Executable(PathBuf::from(BACKBOARDD)));
+	assert_eq!(resolve("backboardd"), backboardd);
+	assert_eq!(resolve("com.apple.backboardd"), backboardd);
+	assert_eq!(resolve("/usr/libexec/backboardd"), backboardd);
+
+	assert_eq!(resolve("nonexistent"), None);
+}
+
+#[test]
+fn existing_executables_are_targets() {
+	// Any absolute path to a file that exists is a target, even if it isn't an app or daemon.
+	let file = TempFile::new("executable", "");
+	assert_eq!(
+		resolve(file.0.to_str().unwrap()),
+		Some(Target::Executable(file.0.clone()))
+	);
+	assert_eq!(resolve("/nonexistent/executable"), None);
+}
+
+#[test]
+fn manifests() {
+	let file = TempFile::new(
+		"manifest.plist",
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>tweaks</key>
+	<array><string>Alpha.dylib</string></array>
+	<key>apps</key>
+	<array>
+		<dict>
+			<key>identifier</key><string>com.apple.mobilesafari</string>
+			<key>name</key><string>Safari</string>
+		</dict>
+	</array>
+	<key>daemons</key>
+	<array>
+		<dict>
+			<key>label</key><string>com.apple.backboardd</string>
+			<key>executable</key><string>/usr/libexec/backboardd</string>
+		</dict>
+	</array>
+</dict>
+</plist>"#,
+	);
+	let manifest = Manifest::from_file(&file.0).unwrap();
+	assert_eq!(manifest.tweaks.tweaks().unwrap(), ["Alpha.dylib"]);
+	assert_eq!(
+		Target::resolve("safari", &manifest.apps, &manifest.daemons).unwrap(),
+		Some(Target::App("com.apple.mobilesafari".to_string()))
+	);
+	assert_eq!(
+		Target::resolve("backboardd", &manifest.apps, &manifest.daemons).unwrap(),
+		Some(Target::Executable(PathBuf::from("/usr/libexec/backboardd")))
+	);
+}
+
+#[test]
+fn partial_manifests() {
+	// Everything in a manifest is optional.
+	let file = TempFile::new(
+		"partial.plist",
+		r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>tweaks</key>
+	<array><string>Alpha.dylib</string></array>
+</dict>
+</plist>"#,
+	);
+	let manifest = Manifest::from_file(&file.0).unwrap();
+	assert_eq!(manifest.tweaks, ["Alpha.dylib"]);
+	assert!(manifest.apps.is_empty());
+	assert!(manifest.daemons.is_empty());
+
+	let broken = TempFile::new("broken.plist", "this isn't a plist");
+	assert!(Manifest::from_file(&broken.0).is_err());
+}
@@ -0,0 +1,265 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that bender never throws away anything it doesn't understand
+// when it rewrites libhooker's configuration.
+
+mod common;
+
+use bender_core::{
+	libhooker::{LibhookerConfig, Target, TweakMode},
+	store::ConfigFile,
+};
+use common::TempFile;
+use plist::{Dictionary, Value};
+use std::collections::BTreeMap;
+
+fn dict(entries: Vec<(&str, Value)>) -> Value {
+	Value::Dictionary(
+		entries
+			.into_iter()
+			.map(|(key, value)| (key.to_string(), value))
+			.collect::<Dictionary>(),
+	)
+}
+
+fn tweak_config(custom_config: bool, entries: Vec<(&str, Value)>) -> Value {
+	let mut config = vec![
+		("customConfig", Value::Boolean(custom_config)),
+		("enableTweaks", Value::Boolean(true)),
+		("allowDeny", Value::Integer(1.into())),
+		(
+			"tweakConfigs",
+			dict(vec![
+				("Alpha", Value::Boolean(true)),
+				("Beta", Value::Boolean(false)),
+			]),
+		),
+	];
+	config.extend(entries);
+	dict(config)
+}
+
+/// A configuration like libhooker would write, plus keys bender knows nothing about at every level.
+fn sample() -> Value {
+	dict(vec![
+		("webProcessTweaks", Value::Boolean(false)),
+		("memPrefs", dict(vec![("Alpha", Value::Boolean(false))])),
+		("futureTopLevelKey", Value::String("hello".to_string())),
+		("futureData", Value::Data(vec![0xde, 0xad, 0xbe, 0xef])),
+		(
+			"tweakconfigs",
+			dict(vec![
+				(
+					"bundles",
+					dict(vec![(
+						"com.apple.mobilesafari",
+						tweak_config(true, vec![("futureBundleKey", Value::Integer(42.into()))]),
+					)]),
+				),
+				(
+					"paths",
+					dict(vec![(
+						"/usr/libexec/backboardd",
+						tweak_config(false, vec![("futurePathKey", Value::Real(1.5))]),
+					)]),
+				),
+				(
+					"default",
+					tweak_config(
+						false,
+						vec![(
+							"futureDefaultKey",
+							Value::Array(vec![Value::String("nested".to_string())]),
+						)],
+					),
+				),
+				(
+					"futureTargetKind",
+					dict(vec![("foo", Value::Boolean(true))]),
+				),
+			]),
+		),
+	])
+}
+
+/// Write a plist value out, read it as a libhooker configuration, write that back out,
+/// and read it back in again as a plain plist value.
+fn roundtrip(name: &str, value: &Value, edit: impl FnOnce(&mut LibhookerConfig)) -> Value {
+	let file = TempFile::empty(&format!("{}.plist", name));
+	value
+		.to_file_binary(&file.0)
+		.expect("failed to write sample");
+	let mut config = LibhookerConfig::from_file(&file.0).expect("failed to read sample");
+	edit(&mut config);
+	config.to_file(&file.0).expect("failed to write config");
+	Value::from_file(&file.0).expect("failed to read config")
+}
+
+#[test]
+fn unknown_keys_survive() {
+	let sample = sample();
+	assert_eq!(roundtrip("unknown", &sample, |_| {}), sample);
+}
+
+#[test]
+fn libhooker_defaults_survive() {
+	// A default configuration that denies nothing is still saved as it was.
+	let sample = dict(vec![
+		("webProcessTweaks", Value::Boolean(true)),
+		(
+			"tweakconfigs",
+			dict(vec![
+				("bundles", dict(vec![])),
+				("paths", dict(vec![])),
+				(
+					"default",
+					dict(vec![
+						("customConfig", Value::Boolean(false)),
+						("enableTweaks", Value::Boolean(true)),
+						("allowDeny", Value::Integer(0.into())),
+						(
+							"tweakConfigs",
+							dict(vec![
+								("Alpha", Value::Boolean(false)),
+								("Beta", Value::Boolean(false)),
+							]),
+						),
+					]),
+				),
+			]),
+		),
+	]);
+	assert_eq!(roundtrip("defaults", &sample, |_| {}), sample);
+}
+
+#[test]
+fn disabled_default_survives() {
+	// Every tweak "loads" in a default configuration with no entries,
+	// but that doesn't matter if tweak loading is off entirely.
+	let sample = dict(vec![
+		("webProcessTweaks", Value::Boolean(true)),
+		(
+			"tweakconfigs",
+			dict(vec![
+				("bundles", dict(vec![])),
+				("paths", dict(vec![])),
+				(
+					"default",
+					dict(vec![
+						("customConfig", Value::Boolean(false)),
+						("enableTweaks", Value::Boolean(false)),
+						("allowDeny", Value::Integer(0.into())),
+						("tweakConfigs", dict(vec![])),
+					]),
+				),
+			]),
+		),
+	]);
+	assert_eq!(roundtrip("disabled", &sample, |_| {}), sample);
+}
+
+#[test]
+fn missing_default_stays_missing() {
+	// Nothing should be added to a plist that didn't have a default configuration.
+	let sample = dict(vec![
+		("webProcessTweaks", Value::Boolean(true)),
+		(
+			"tweakconfigs",
+			dict(vec![("bundles", dict(vec![])), ("paths", dict(vec![]))]),
+		),
+	]);
+	assert_eq!(roundtrip("missing-default", &sample, |_| {}), sample);
+}
+
+#[test]
+fn unknown_keys_survive_edits() {
+	let sample = sample();
+	let result = roundtrip("edits", &sample, |config| {
+		let mut changes = BTreeMap::new();
+		changes.insert("Beta".to_string(), Some(true));
+		config.configure(
+			&Target::App("com.apple.mobilesafari".to_string()),
+			TweakMode::Allow,
+			None,
+			None,
+			changes,
+		);
+		let mut compat = BTreeMap::new();
+		compat.insert("Beta".to_string(), false);
+		config.set_compat(compat);
+	});
+
+	let top = result.as_dictionary().unwrap();
+	assert_eq!(
+		top.get("futureTopLevelKey"),
+		Some(&Value::String("hello".to_string()))
+	);
+	assert_eq!(
+		top.get("futureData"),
+		Some(&Value::Data(vec![0xde, 0xad, 0xbe, 0xef]))
+	);
+	let tweakconfigs = top.get("tweakconfigs").unwrap().as_dictionary().unwrap();
+	assert!(tweakconfigs.contains_key("futureTargetKind"));
+	let safari = tweakconfigs
+		.get("bundles")
+		.and_then(Value::as_dictionary)
+		.and_then(|bundles| bundles.get("com.apple.mobilesafari"))
+		.and_then(Value::as_dictionary)
+		.unwrap();
+	assert_eq!(
+		safari.get("futureBundleKey"),
+		Some(&Value::Integer(42.into()))
+	);
+	assert_eq!(
+		safari
+			.get("tweakConfigs")
+			.and_then(Value::as_dictionary)
+			.and_then(|tweaks| tweaks.get("Beta")),
+		Some(&Value::Boolean(true))
+	);
+	let mem_prefs = top.get("memPrefs").unwrap().as_dictionary().unwrap();
+	assert_eq!(mem_prefs.get("Beta"), Some(&Value::Boolean(false)));
+	assert!(tweakconfigs
+		.get("default")
+		.and_then(Value::as_dictionary)
+		.unwrap()
+		.contains_key("futureDefaultKey"));
+}
+
+#[test]
+fn removing_isnt_unsetting() {
+	// `--remove` stores an explicit "off", while `--unset` takes the entry out entirely.
+	// They load the same tweaks, but they have to be told apart after saving.
+	let safari = Target::App("com.apple.mobilesafari".to_string());
+	let save = |name: &str, entry: Option<bool>| {
+		let file = TempFile::empty(&format!("{}.plist", name));
+		let mut config_file = ConfigFile::new(&file.0);
+		let mut config = config_file.load().unwrap();
+		let mut changes = BTreeMap::new();
+		changes.insert("Alpha.dylib".to_string(), entry);
+		config.configure(&safari, TweakMode::Deny, Some(true), None, changes);
+		config_file.save(&config, None).unwrap();
+		ConfigFile::new(&file.0)
+			.load()
+			.unwrap()
+			.tweak_configs
+			.bundles
+			.remove("com.apple.mobilesafari")
+			.unwrap()
+	};
+	let removed = save("removed", Some(false));
+	let unset = save("unset", None);
+	assert_eq!(removed.tweak_entry("Alpha.dylib"), Some(false));
+	assert_eq!(unset.tweak_entry("Alpha.dylib"), None);
+	assert_eq!(
+		removed.will_tweak_load("Alpha.dylib"),
+		unset.will_tweak_load("Alpha.dylib")
+	);
+}
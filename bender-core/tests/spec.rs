@@ -0,0 +1,242 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that `bender apply` does what the spec says, every time,
+// and refuses specs that don't make sense.
+
+mod common;
+
+use bender_core::{
+	diff::diff,
+	libhooker::{LibhookerConfig, Target, TweakMode, COMPAT_SUBSTRATE},
+	spec::Spec,
+	store::ConfigFile,
+};
+use common::{apps, daemons, tweaks, TempFile, BACKBOARDD};
+use plist::Value;
+use std::path::PathBuf;
+
+fn parse(name: &str, contents: &str) -> Spec {
+	let file = TempFile::new(name, contents);
+	Spec::from_file(&file.0).expect("failed to read spec")
+}
+
+fn apply(spec: &Spec, config: &LibhookerConfig) -> eyre::Result<LibhookerConfig> {
+	spec.apply(config, &tweaks(), &apps(), &daemons())
+}
+
+const SPEC: &str = r#"
+web_process_tweaks = false
+
+[compat]
+Alpha = "substrate"
+Beta = "libhooker"
+
+[[targets]]
+name = "Safari"
+mode = "allow"
+tweaks = ["Alpha", "beta"]
+
+[[targets]]
+name = "backboardd"
+enable_tweaks = false
+
+[[targets]]
+name = "default"
+tweaks = ["Gamma"]
+"#;
+
+#[test]
+fn applying() {
+	let config = apply(&parse("apply.toml", SPEC), &LibhookerConfig::default()).unwrap();
+	assert!(!config.web_process_tweaks);
+	assert_eq!(
+		config.memory_compat_prefs.into_iter().collect::<Vec<_>>(),
+		vec![("Alpha.dylib".to_string(), COMPAT_SUBSTRATE)]
+	);
+
+	let configs = &config.tweak_configs;
+	let safari = Target::App("com.apple.mobilesafari".to_string())
+		.find_config(configs)
+		.unwrap();
+	assert!(safari.custom_config);
+	assert_eq!(safari.allow_or_deny, TweakMode::Allow);
+	assert!(safari.will_tweak_load("Alpha.dylib"));
+	assert!(safari.will_tweak_load("Beta.dylib"));
+	assert!(!safari.will_tweak_load("Gamma.dylib"));
+
+	let backboardd = Target::Executable(PathBuf::from(BACKBOARDD))
+		.find_config(configs)
+		.unwrap();
+	assert!(!backboardd.enable_tweaks);
+
+	// The default configuration is the one thing that doesn't get custom configuration turned on.
+	assert!(!configs.default.custom_config);
+	assert!(!configs.default.will_tweak_load("Gamma.dylib"));
+	assert!(configs.default.will_tweak_load("Alpha.dylib"));
+}
+
+#[test]
+fn yaml_specs() {
+	let toml = parse("same.toml", SPEC);
+	let yaml = parse(
+		"same.yaml",
+		r#"
+web_process_tweaks: false
+compat:
+  Alpha: substrate
+  Beta: libhooker
+targets:
+  - name: Safari
+    mode: allow
+    tweaks: [Alpha, beta]
+  - name: backboardd
+    enable_tweaks: false
+  - name: default
+    tweaks: [Gamma]
+"#,
+	);
+	let config = LibhookerConfig::default();
+	assert!(diff(
+		&apply(&toml, &config).unwrap(),
+		&apply(&yaml, &config).unwrap()
+	)
+	.is_empty());
+}
+
+#[test]
+fn applying_twice_changes_nothing() {
+	let spec = parse("twice.toml", SPEC);
+	let once = apply(&spec, &LibhookerConfig::default()).unwrap();
+	let twice = apply(&spec, &once).unwrap();
+	assert!(diff(&once, &twice).is_empty());
+}
+
+#[test]
+fn saved_specs_arent_drifted() {
+	// A target that's listed, but left using the default configuration, is the easiest to get wrong.
+	let spec = parse(
+		"saved.toml",
+		&format!(
+			"{}\n[[targets]]\nname = \"Foo App\"\ncustom_config = false\n",
+			SPEC
+		),
+	);
+	let file = TempFile::empty("saved.plist");
+	let mut config_file = ConfigFile::new(&file.0);
+	let config = config_file.load().unwrap();
+	let desired = apply(&spec, &config).unwrap();
+	config_file.save(&desired, None).unwrap();
+
+	// This is exactly what `bender apply --check` does after `bender apply`.
+	let saved = ConfigFile::new(&file.0).load().unwrap();
+	assert!(diff(&saved, &apply(&spec, &saved).unwrap()).is_empty());
+	assert!(diff(&desired, &saved).is_empty());
+}
+
+#[test]
+fn sections_are_the_whole_truth() {
+	let mut config = apply(&parse("before.toml", SPEC), &LibhookerConfig::default()).unwrap();
+	config
+		.tweak_configs
+		.extra
+		.insert("futureKey".to_string(), Value::Boolean(true));
+	let config = apply(
+		&parse(
+			"after.toml",
+			r#"
+[[targets]]
+name = "Safari"
+tweaks = ["Gamma"]
+"#,
+		),
+		&config,
+	)
+	.unwrap();
+	let configs = &config.tweak_configs;
+	// Targets that aren't listed lose their configuration...
+	assert!(Target::Executable(PathBuf::from(BACKBOARDD))
+		.find_config(configs)
+		.is_none());
+	assert!(configs.default.is_unset());
+	// ...but sections that aren't in the spec, and keys we don't know about, are left alone.
+	assert!(!config.web_process_tweaks);
+	assert!(config.memory_compat_prefs.contains_key("Alpha.dylib"));
+	assert!(configs.extra.contains_key("futureKey"));
+
+	let safari = Target::App("com.apple.mobilesafari".to_string())
+		.find_config(configs)
+		.unwrap();
+	assert_eq!(safari.allow_or_deny, TweakMode::Deny);
+	assert!(!safari.will_tweak_load("Gamma.dylib"));
+	assert!(safari.will_tweak_load("Alpha.dylib"));
+}
+
+#[test]
+fn duplicate_targets() {
+	// The same target, by two different names.
+	let spec = parse(
+		"duplicate.toml",
+		r#"
+[[targets]]
+name = "Safari"
+
+[[targets]]
+name = "com.apple.mobilesafari"
+"#,
+	);
+	let err = apply(&spec, &LibhookerConfig::default()).unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"com.apple.mobilesafari is in the targets more than once"
+	);
+}
+
+#[test]
+fn missing_tweaks() {
+	let targets = parse(
+		"missing-target-tweak.toml",
+		r#"
+[[targets]]
+name = "Safari"
+tweaks = ["Alpha", "Nonexistent"]
+"#,
+	);
+	let err = apply(&targets, &LibhookerConfig::default()).unwrap_err();
+	assert_eq!(err.to_string(), "tweak Nonexistent is not installed");
+
+	let compat = parse(
+		"missing-compat-tweak.toml",
+		r#"
+[compat]
+Nonexistent = "substrate"
+"#,
+	);
+	let err = apply(&compat, &LibhookerConfig::default()).unwrap_err();
+	assert_eq!(err.to_string(), "tweak Nonexistent is not installed");
+}
+
+#[test]
+fn bad_specs() {
+	let missing_target = parse(
+		"missing-target.toml",
+		r#"
+[[targets]]
+name = "Nonexistent"
+"#,
+	);
+	let err = apply(&missing_target, &LibhookerConfig::default()).unwrap_err();
+	assert_eq!(err.to_string(), "Nonexistent is not a valid target");
+
+	// Typos are errors, rather than being quietly ignored.
+	let typo = TempFile::new("typo.toml", "web_proccess_tweaks = true\n");
+	assert!(Spec::from_file(&typo.0).is_err());
+	let extension = TempFile::new("spec.json", "{}");
+	assert!(Spec::from_file(&extension.0).is_err());
+}
@@ -0,0 +1,109 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that saving the configuration never clobbers changes made by something else,
+// and never leaves anything half-written lying around.
+
+mod common;
+
+use bender_core::{libhooker::LibhookerConfig, store::ConfigFile};
+use common::TempDir;
+use std::{
+	os::unix::fs::PermissionsExt,
+	path::{Path, PathBuf},
+};
+
+const PLIST: &str = "org.coolstar.libhooker.plist";
+
+/// Everything in a directory, so we can tell if bender left anything behind.
+fn contents(dir: &Path) -> Vec<PathBuf> {
+	let mut contents = std::fs::read_dir(dir)
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.collect::<Vec<_>>();
+	contents.sort();
+	contents
+}
+
+fn changed() -> LibhookerConfig {
+	LibhookerConfig {
+		web_process_tweaks: false,
+		..LibhookerConfig::default()
+	}
+}
+
+#[test]
+fn saving() {
+	let dir = TempDir::new("store-saving");
+	let path = dir.0.join(PLIST);
+	let mut config_file = ConfigFile::new(&path);
+	// There's no configuration yet, so it's the default one.
+	assert!(config_file.load().unwrap().web_process_tweaks);
+	config_file.save(&changed(), None).unwrap();
+	assert!(!ConfigFile::new(&path).load().unwrap().web_process_tweaks);
+	// Saving again goes against what we just wrote, rather than what was there when we loaded it.
+	config_file.save(&LibhookerConfig::default(), None).unwrap();
+	assert_eq!(contents(&dir.0), [path]);
+}
+
+#[test]
+fn changed_behind_our_back() {
+	let dir = TempDir::new("store-changed");
+	let path = dir.0.join(PLIST);
+	LibhookerConfig::default().to_file(&path).unwrap();
+	let mut config_file = ConfigFile::new(&path);
+	config_file.load().unwrap();
+
+	// Something like libhooker-configurator saves in the meantime.
+	changed().to_file(&path).unwrap();
+	let theirs = std::fs::read(&path).unwrap();
+	let err = config_file
+		.save(&LibhookerConfig::default(), None)
+		.unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		format!(
+			"{} was changed by something else while bender was running, try again",
+			path.display()
+		)
+	);
+	assert_eq!(std::fs::read(&path).unwrap(), theirs);
+	assert_eq!(contents(&dir.0), [path.clone()]);
+
+	// The same goes for something deleting it.
+	std::fs::remove_file(&path).unwrap();
+	assert!(config_file.save_raw(&theirs, None).is_err());
+	assert!(contents(&dir.0).is_empty());
+}
+
+#[test]
+fn failed_saves_leave_nothing_behind() {
+	// The name is fine, but the temporary file next to it gets a name that's too long to exist.
+	let dir = TempDir::new("store-failed");
+	let path = dir.0.join(format!("{}.plist", "a".repeat(240)));
+	std::fs::write(&path, "original").unwrap();
+	let mut config_file = ConfigFile::new(&path);
+	config_file.load_raw().unwrap();
+	assert!(config_file.save_raw(b"new", None).is_err());
+	assert_eq!(std::fs::read(&path).unwrap(), b"original");
+	assert_eq!(contents(&dir.0), [path]);
+}
+
+#[test]
+fn permissions_are_kept() {
+	let dir = TempDir::new("store-permissions");
+	let path = dir.0.join(PLIST);
+	LibhookerConfig::default().to_file(&path).unwrap();
+	std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o604)).unwrap();
+	let mut config_file = ConfigFile::new(&path);
+	config_file.load().unwrap();
+	config_file.save(&changed(), None).unwrap();
+	let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+	assert_eq!(mode & 0o777, 0o604);
+}
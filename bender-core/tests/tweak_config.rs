@@ -0,0 +1,191 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+// These make sure that bender reads tweak entries the same way libhooker does.
+
+mod common;
+
+use bender_core::{
+	explain::explain,
+	libhooker::{ConfigTypes, LibhookerConfig, Target, TweakConfig, TweakMode},
+};
+use common::BACKBOARDD;
+use std::path::PathBuf;
+
+fn tweak_config(mode: TweakMode, entries: &[(&str, bool)]) -> TweakConfig {
+	TweakConfig {
+		allow_or_deny: mode,
+		tweak_configs: entries
+			.iter()
+			.map(|(tweak, value)| (tweak.to_string(), *value))
+			.collect(),
+		..TweakConfig::default()
+	}
+}
+
+#[test]
+fn missing_entries_are_off() {
+	let deny = tweak_config(TweakMode::Deny, &[("Denied.dylib", true)]);
+	assert!(!deny.will_tweak_load("Denied.dylib"));
+	// libhooker reads a missing entry as "off", which a denylist doesn't deny.
+	assert!(deny.will_tweak_load("Missing.dylib"));
+
+	let allow = tweak_config(TweakMode::Allow, &[("Allowed.dylib", true)]);
+	assert!(allow.will_tweak_load("Allowed.dylib"));
+	assert!(!allow.will_tweak_load("Missing.dylib"));
+}
+
+#[test]
+fn nothing_loads_with_tweaks_disabled() {
+	let disabled = TweakConfig {
+		enable_tweaks: false,
+		..tweak_config(TweakMode::Deny, &[])
+	};
+	assert!(!disabled.will_tweak_load("Missing.dylib"));
+}
+
+#[test]
+fn agrees_with_explain() {
+	for mode in [TweakMode::Allow, TweakMode::Deny].iter() {
+		let configs = ConfigTypes {
+			default: tweak_config(*mode, &[("On.dylib", true), ("Off.dylib", false)]),
+			..ConfigTypes::default()
+		};
+		for tweak in ["On.dylib", "Off.dylib", "Missing.dylib"].iter() {
+			assert_eq!(
+				configs.default.will_tweak_load(tweak),
+				explain(&configs, &Target::Default, None, tweak, None).loads,
+				"{} in {} mode",
+				tweak,
+				mode.name()
+			);
+		}
+	}
+}
+
+#[test]
+fn switching_modes_keeps_what_loads() {
+	let installed = ["On.dylib", "Off.dylib", "Missing.dylib"]
+		.iter()
+		.map(|tweak| tweak.to_string())
+		.collect::<Vec<String>>();
+	// Entries for tweaks that aren't installed anymore are kept working too.
+	let every_tweak = installed
+		.iter()
+		.map(String::as_str)
+		.chain(std::iter::once("Uninstalled.dylib"))
+		.collect::<Vec<&str>>();
+	for (from, to) in [
+		(TweakMode::Allow, TweakMode::Deny),
+		(TweakMode::Deny, TweakMode::Allow),
+	]
+	.iter()
+	{
+		let before = tweak_config(
+			*from,
+			&[
+				("On.dylib", true),
+				("Off.dylib", false),
+				("Uninstalled.dylib", true),
+			],
+		);
+		let mut after = before.clone();
+		after.switch_mode(*to, &installed);
+		assert_eq!(after.allow_or_deny, *to);
+		for tweak in every_tweak.iter() {
+			assert_eq!(
+				before.will_tweak_load(tweak),
+				after.will_tweak_load(tweak),
+				"{} from {} mode to {} mode",
+				tweak,
+				from,
+				to
+			);
+		}
+
+		// And switching back changes nothing about what loads either.
+		after.switch_mode(*from, &installed);
+		for tweak in every_tweak.iter() {
+			assert_eq!(
+				before.will_tweak_load(tweak),
+				after.will_tweak_load(tweak),
+				"{} back to {} mode",
+				tweak,
+				from
+			);
+		}
+	}
+}
+
+/// A configuration with custom configurations in both modes, and one that's off.
+fn with_targets() -> LibhookerConfig {
+	let mut config = LibhookerConfig::default();
+	let configs = &mut config.tweak_configs;
+	configs.paths.insert(
+		PathBuf::from(BACKBOARDD),
+		TweakConfig {
+			custom_config: true,
+			..tweak_config(TweakMode::Allow, &[])
+		},
+	);
+	configs.bundles.insert(
+		"com.apple.mobilesafari".to_string(),
+		TweakConfig {
+			custom_config: true,
+			..tweak_config(TweakMode::Deny, &[])
+		},
+	);
+	configs.bundles.insert(
+		"com.example.foo".to_string(),
+		tweak_config(TweakMode::Allow, &[("Tweak.dylib", true)]),
+	);
+	config
+}
+
+#[test]
+fn loading_everywhere() {
+	for load in [true, false].iter() {
+		let mut config = with_targets();
+		let skipped = config.set_tweak_loads("Tweak.dylib", *load, true);
+		let configs = &config.tweak_configs;
+		assert_eq!(configs.default.will_tweak_load("Tweak.dylib"), *load);
+		for tweak_config in configs.paths.values().chain(configs.bundles.values()) {
+			if tweak_config.custom_config {
+				assert_eq!(tweak_config.will_tweak_load("Tweak.dylib"), *load);
+			}
+		}
+
+		// libhooker ignores targets with custom configuration off, so they're left alone and reported.
+		assert_eq!(skipped, [Target::App("com.example.foo".to_string())]);
+		assert_eq!(
+			configs.bundles["com.example.foo"].tweak_entry("Tweak.dylib"),
+			Some(true)
+		);
+	}
+}
+
+#[test]
+fn loading_by_default_only() {
+	let mut config = with_targets();
+	let before = config.tweak_configs.clone();
+	assert!(config
+		.set_tweak_loads("Tweak.dylib", false, false)
+		.is_empty());
+	let configs = &config.tweak_configs;
+	assert!(!configs.default.will_tweak_load("Tweak.dylib"));
+	let entries = |configs: &ConfigTypes| {
+		configs
+			.paths
+			.values()
+			.chain(configs.bundles.values())
+			.map(|tweak_config| tweak_config.tweak_configs.clone())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(entries(configs), entries(&before));
+}
@@ -1,4 +1,7 @@
 fn main() {
-	println!("cargo:rustc-link-lib=framework=Foundation");
-	println!("cargo:rustc-link-lib=framework=MobileCoreServices");
+	// Only iOS has these frameworks, the rest of the time we don't use them anyways.
+	if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("ios") {
+		println!("cargo:rustc-link-lib=framework=Foundation");
+		println!("cargo:rustc-link-lib=framework=MobileCoreServices");
+	}
 }
@@ -7,14 +7,28 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
+use bender_core::apps::{App, AppCatalog};
+use color_eyre::eyre::Result;
 use objc::runtime::{Class, Object};
 use objc_foundation::{INSArray, INSString, NSArray, NSObject, NSString};
 use objc_id::Id;
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use std::path::{Path, PathBuf};
 
-// This is a "lazy static" global, it's initialized on first use,
-// then reused for subsequent uses.
-pub static APPS: Lazy<Vec<App>> = Lazy::new(|| {
+/// Gets the installed apps from the live system, using `LSApplicationWorkspace`.
+#[derive(Default)]
+pub struct WorkspaceApps {
+	apps: OnceCell<Vec<App>>,
+}
+
+impl AppCatalog for WorkspaceApps {
+	fn apps(&self) -> Result<&[App]> {
+		// This is initialized on first use, then reused for subsequent uses.
+		Ok(self.apps.get_or_init(load_workspace_apps))
+	}
+}
+
+fn load_workspace_apps() -> Vec<App> {
 	// This is a pain, so bare with me.
 	// Also, the various `unsafe { }` blocks are due to the fact that the Objective-C interop macros,
 	// such as msg_send! and sel!, call external functions over FFI. Rust considers FFI to be "unsafe"
@@ -58,15 +72,37 @@ pub static APPS: Lazy<Vec<App>> = Lazy::new(|| {
 			// Get the "localized name" of this app,
 			// by calling `localizedName` on the app information object.
 			let localized_name: &NSString = unsafe { msg_send![app, localizedName] };
+			// Get the path to the app's executable, so that filters and targets can match it by executable too.
+			let executable = bundle_executable(app);
 			// Now, we finish up and convert the NSStrings to Rust Strings,
 			// creating our own App struct with them.
 			App {
 				identifier: identifier.as_str().to_string(),
 				name: localized_name.as_str().to_string(),
+				executable,
+				version: None,
 			}
 		})
 		.collect()
-});
+}
+
+/// Get the full path to an app's executable from it's `LSApplicationProxy`,
+/// by joining the path of `bundleURL` with `bundleExecutable`.
+/// Either of them can be nil, such as for placeholders of apps that are still installing.
+fn bundle_executable(app: &NSObject) -> Option<PathBuf> {
+	let bundle_url: *mut Object = unsafe { msg_send![app, bundleURL] };
+	let executable: *mut NSString = unsafe { msg_send![app, bundleExecutable] };
+	if bundle_url.is_null() || executable.is_null() {
+		return None;
+	}
+	// `path` turns the file URL into a plain path, like `/Applications/MobileSafari.app`.
+	let bundle_path: *mut NSString = unsafe { msg_send![bundle_url, path] };
+	if bundle_path.is_null() {
+		return None;
+	}
+	let (bundle_path, executable) = unsafe { (&*bundle_path, &*executable) };
+	Some(Path::new(bundle_path.as_str()).join(executable.as_str()))
+}
 
 #[allow(improper_ctypes)]
 extern "C" {
@@ -74,29 +110,3 @@ extern "C" {
 	// a part of Foundation.framework on macOS and iOS.
 	fn NSClassFromString(name: Id<NSString>) -> *mut Class;
 }
-
-pub struct App {
-	pub identifier: String,
-	pub name: String,
-}
-
-pub fn fix_app_name(input: &str) -> Option<String> {
-	let normalized_input = input.trim().to_lowercase();
-	APPS.iter()
-		.find(|app| {
-			let id = app.identifier.trim().to_lowercase();
-			let name = app.name.trim().to_lowercase();
-
-			id == normalized_input
-				|| name == normalized_input
-				|| name.replace(' ', "") == normalized_input.replace(' ', "")
-		})
-		.map(|app| app.identifier.clone())
-}
-
-pub fn get_friendly_app_name(id: &str) -> String {
-	APPS.iter()
-		.find(|app| app.identifier == id)
-		.map(|app| app.name.clone())
-		.expect("some idiot used get_friendly_app_name on unchecked input")
-}
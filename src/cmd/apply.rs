@@ -0,0 +1,84 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{
+	output::{self, Record},
+	SaveOpts,
+};
+use crate::providers::Providers;
+use bender_core::{
+	diff::{diff, Change},
+	libhooker::LibhookerConfig,
+	spec::Spec,
+	store::ConfigFile,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use serde::Serialize;
+use std::path::Path;
+
+/// The result of `bender apply --check`, for machine-readable output.
+#[derive(Serialize)]
+struct CheckReport {
+	/// Whether the configuration doesn't match the file.
+	drifted: bool,
+	/// What applying the file would change.
+	changes: Vec<Change>,
+}
+
+impl Record for CheckReport {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.drifted.to_string(), self.changes.len().to_string()]
+	}
+}
+
+pub fn apply(
+	config_file: &mut ConfigFile,
+	config: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+	file: &Path,
+	check: bool,
+) -> Result<()> {
+	let desired = Spec::from_file(file)?.apply(
+		config,
+		providers.tweaks.as_ref(),
+		providers.apps.as_ref(),
+		providers.services.as_ref(),
+	)?;
+
+	if check {
+		// Saving writes `desired` exactly as it is, so this is the same comparison `apply` makes,
+		// and a configuration that `apply` just saved never looks drifted.
+		let changes = diff(config, &desired);
+		if !output::is_text() {
+			let drifted = !changes.is_empty();
+			output::print_record(&CheckReport { drifted, changes })?;
+			if drifted {
+				std::process::exit(1);
+			}
+			return Ok(());
+		}
+		if changes.is_empty() {
+			println!(
+				"The configuration matches {}",
+				file.display().to_string().green()
+			);
+			return Ok(());
+		}
+		println!(
+			"The configuration has drifted from {}:",
+			file.display().to_string().yellow()
+		);
+		super::diff::print_changes(changes, providers);
+		std::process::exit(1);
+	}
+
+	super::save(config_file, config, &desired, providers, opts)
+}
@@ -0,0 +1,47 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{cmd::output, providers::Providers};
+use bender_core::libhooker::{LibhookerConfig, Target};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+
+/// Copy the source target's whole configuration to every destination, replacing their own.
+pub fn clone(
+	config: &mut LibhookerConfig,
+	providers: &Providers,
+	source: Target,
+	destinations: Vec<Target>,
+) -> Result<()> {
+	let apps = providers.apps.as_ref();
+	if source.find_config(&config.tweak_configs).is_none() {
+		eprintln!(
+			"{} has no configuration of it's own to clone!\nUse `{}` to see which targets do!",
+			source.display(apps).to_string().red(),
+			"bender view".green()
+		);
+		std::process::exit(1);
+	}
+
+	for destination in destinations {
+		if destination == source {
+			continue;
+		}
+		if output::is_text() {
+			println!(
+				"{} {} to {}",
+				"CLONING".green(),
+				source.display(apps).to_string().yellow(),
+				destination.display(apps).to_string().yellow()
+			);
+		}
+		config.tweak_configs.clone_config(&source, &destination);
+	}
+	Ok(())
+}
@@ -7,24 +7,33 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
-use crate::libhooker::{LibhookerConfig, Target, TweakMode};
+use crate::cmd::output;
+use bender_core::libhooker::{LibhookerConfig, Target, TweakConfig, TweakMode};
 use color_eyre::eyre::Result;
 use colorful::Colorful;
 use std::collections::BTreeMap;
 
+#[allow(clippy::too_many_arguments)]
 pub fn configure(
-	mut config: LibhookerConfig,
+	config: &mut LibhookerConfig,
 	target: Target,
 	custom_config: Option<bool>,
 	enable_tweaks: Option<bool>,
 	mode: TweakMode,
-	changes: BTreeMap<String, bool>,
+	invert: bool,
+	installed_tweaks: &[String],
+	changes: BTreeMap<String, Option<bool>>,
 ) -> Result<()> {
-	assert!(std::path::PathBuf::from("/.procursus_strapped").is_file());
-	let tweak_config = target.get_config(&mut config.tweak_configs);
-	tweak_config.allow_or_deny = mode;
-	tweak_config.custom_config = custom_config.unwrap_or(tweak_config.custom_config);
-	tweak_config.enable_tweaks = enable_tweaks.unwrap_or(tweak_config.enable_tweaks);
+	// Switch the mode before anything else, so that the changes are made in the new mode.
+	if let Some(tweak_config) = target.find_config_mut(&mut config.tweak_configs) {
+		if tweak_config.allow_or_deny != mode {
+			switch_mode(tweak_config, mode, invert, installed_tweaks);
+		}
+	}
+	let tweak_config = config.configure(&target, mode, custom_config, enable_tweaks, changes);
+	if !output::is_text() {
+		return Ok(());
+	}
 	println!(
 		"{} is {}",
 		"custom configuration".blue(),
@@ -37,24 +46,65 @@ pub fn configure(
 	println!(
 		"{} is {}",
 		"tweak loading".blue(),
-		if tweak_config.custom_config {
+		if tweak_config.enable_tweaks {
 			"on".green()
 		} else {
 			"off".red()
 		}
 	);
-	tweak_config.tweak_configs.extend(changes);
 
-	plist::to_file_binary(
-		"/var/mobile/Library/Preferences/org.coolstar.libhooker.plist",
-		&config,
-	)?;
+	Ok(())
+}
 
-	println!(
-		"Ensure to {} or {} your device to apply the changes!",
-		"respring".yellow(),
-		"userspace reboot".magenta()
-	);
+/// Switch a target between allowlist and denylist mode, and sum up what that does to which tweaks load.
+fn switch_mode(
+	tweak_config: &mut TweakConfig,
+	mode: TweakMode,
+	invert: bool,
+	installed_tweaks: &[String],
+) {
+	let loads = |tweak_config: &TweakConfig, tweak: &str| {
+		tweak_config
+			.allow_or_deny
+			.check(tweak_config.tweak_value(tweak))
+	};
+	let before = installed_tweaks
+		.iter()
+		.map(|tweak| loads(tweak_config, tweak))
+		.collect::<Vec<bool>>();
+	let old_mode = tweak_config.allow_or_deny;
+	if invert {
+		// Keep every entry as-is, which flips what they mean.
+		tweak_config.allow_or_deny = mode;
+	} else {
+		tweak_config.switch_mode(mode, installed_tweaks);
+	}
+	if !output::is_text() {
+		return;
+	}
 
-	Ok(())
+	println!("Switching from {} to {} mode", old_mode, mode);
+	let mut started = Vec::new();
+	let mut stopped = Vec::new();
+	for (tweak, loaded) in installed_tweaks.iter().zip(before) {
+		let name = tweak.strip_suffix(".dylib").unwrap_or(tweak);
+		match (loaded, loads(tweak_config, tweak)) {
+			(false, true) => started.push(name),
+			(true, false) => stopped.push(name),
+			_ => {}
+		}
+	}
+	if started.is_empty() && stopped.is_empty() {
+		println!(
+			"Every entry was rewritten, so the {} tweaks keep loading",
+			"same".green()
+		);
+		return;
+	}
+	if !started.is_empty() {
+		println!("{} {}", "Now loading:".green(), started.join(", "));
+	}
+	if !stopped.is_empty() {
+		println!("{} {}", "No longer loading:".red(), stopped.join(", "));
+	}
 }
@@ -0,0 +1,264 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	cmd::output::{self, Record},
+	providers::Providers,
+};
+use bender_core::{
+	diff::{diff as diff_configs, Change},
+	history::History,
+	libhooker::{LibhookerConfig, Target, COMPAT_LIBHOOKER},
+	spec::CompatMode,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use plist::Value;
+use std::path::Path;
+
+pub fn diff(
+	config: LibhookerConfig,
+	providers: &Providers,
+	history: &History,
+	old: &str,
+	new: &str,
+) -> Result<()> {
+	let changes = diff_configs(&load(old, &config, history)?, &load(new, &config, history)?);
+	if !output::is_text() {
+		return output::print_records(&changes);
+	}
+	if changes.is_empty() {
+		println!("The configurations are the same!");
+		return Ok(());
+	}
+	print_changes(changes, providers);
+	Ok(())
+}
+
+/// Print every change, one per line.
+pub fn print_changes(changes: Vec<Change>, providers: &Providers) {
+	let apps = providers.apps.as_ref();
+	for change in changes {
+		match change {
+			Change::TargetAdded { target } => println!(
+				"{} {} now has a configuration",
+				"+".green(),
+				target.display(apps).to_string().yellow()
+			),
+			Change::TargetRemoved { target } => println!(
+				"{} {} no longer has a configuration",
+				"-".red(),
+				target.display(apps).to_string().yellow()
+			),
+			Change::CustomConfig { target, old, new } => println!(
+				"{} {}: {} {} → {}",
+				"~".blue(),
+				target.display(apps).to_string().yellow(),
+				"custom configuration".blue(),
+				on_off(Some(old)),
+				on_off(Some(new))
+			),
+			Change::EnableTweaks { target, old, new } => println!(
+				"{} {}: {} {} → {}",
+				"~".blue(),
+				target.display(apps).to_string().yellow(),
+				"tweak loading".blue(),
+				on_off(Some(old)),
+				on_off(Some(new))
+			),
+			Change::Mode { target, old, new } => println!(
+				"{} {}: {} mode → {} mode",
+				"~".blue(),
+				target.display(apps).to_string().yellow(),
+				old,
+				new
+			),
+			Change::Tweak {
+				target,
+				tweak,
+				old,
+				new,
+			} => println!(
+				"{} {}: {} {} → {}",
+				match (old, new) {
+					(None, _) => "+".green(),
+					(_, None) => "-".red(),
+					_ => "~".blue(),
+				},
+				target.display(apps).to_string().yellow(),
+				tweak.strip_suffix(".dylib").unwrap_or(&tweak),
+				on_off(old),
+				on_off(new)
+			),
+			Change::Compat { tweak, old, new } => println!(
+				"{} {}: {} → {}",
+				"~".blue(),
+				tweak.strip_suffix(".dylib").unwrap_or(&tweak),
+				compat_mode(old),
+				compat_mode(new)
+			),
+			Change::WebProcessTweaks { old, new } => println!(
+				"{} {} {} → {}",
+				"~".blue(),
+				"web process tweaks".blue(),
+				on_off(Some(old)),
+				on_off(Some(new))
+			),
+			Change::UnknownKey {
+				target,
+				key,
+				old,
+				new,
+			} => println!(
+				"{} {}{} {} → {}",
+				match (&old, &new) {
+					(None, _) => "+".green(),
+					(_, None) => "-".red(),
+					_ => "~".blue(),
+				},
+				target
+					.map(|target| format!("{}: ", target.display(apps).to_string().yellow()))
+					.unwrap_or_default(),
+				key.blue(),
+				unknown_value(old.as_ref()).dark_gray(),
+				unknown_value(new.as_ref()).dark_gray()
+			),
+		}
+	}
+}
+
+// Every change is a row of kind, target, tweak, old, and new, with blanks for anything it doesn't have.
+impl Record for Change {
+	fn tsv(&self) -> Vec<String> {
+		let (kind, target, tweak, old, new) = match self {
+			Change::TargetAdded { target } => ("target_added", Some(target), None, None, None),
+			Change::TargetRemoved { target } => ("target_removed", Some(target), None, None, None),
+			Change::CustomConfig { target, old, new } => (
+				"custom_config",
+				Some(target),
+				None,
+				Some(old.to_string()),
+				Some(new.to_string()),
+			),
+			Change::EnableTweaks { target, old, new } => (
+				"enable_tweaks",
+				Some(target),
+				None,
+				Some(old.to_string()),
+				Some(new.to_string()),
+			),
+			Change::Mode { target, old, new } => (
+				"mode",
+				Some(target),
+				None,
+				Some(old.name().to_string()),
+				Some(new.name().to_string()),
+			),
+			Change::Tweak {
+				target,
+				tweak,
+				old,
+				new,
+			} => (
+				"tweak",
+				Some(target),
+				Some(tweak),
+				old.map(|old| old.to_string()),
+				new.map(|new| new.to_string()),
+			),
+			Change::Compat { tweak, old, new } => (
+				"compat",
+				None,
+				Some(tweak),
+				Some(CompatMode::from(*old).name().to_string()),
+				Some(CompatMode::from(*new).name().to_string()),
+			),
+			Change::WebProcessTweaks { old, new } => (
+				"web_process_tweaks",
+				None,
+				None,
+				Some(old.to_string()),
+				Some(new.to_string()),
+			),
+			Change::UnknownKey {
+				target,
+				key,
+				old,
+				new,
+			} => (
+				"unknown_key",
+				target.as_ref(),
+				Some(key),
+				old.as_ref().map(|old| unknown_value(Some(old))),
+				new.as_ref().map(|new| unknown_value(Some(new))),
+			),
+		};
+		vec![
+			kind.to_string(),
+			output::optional(target.map(Target::key)),
+			output::optional(tweak),
+			output::optional(old),
+			output::optional(new),
+		]
+	}
+}
+
+/// Load one side of the diff: "live" is the current configuration,
+/// and anything else is either a plist file or a snapshot ID.
+fn load(side: &str, live: &LibhookerConfig, history: &History) -> Result<LibhookerConfig> {
+	if side.trim().eq_ignore_ascii_case("live") {
+		return Ok(live.clone());
+	}
+	let path = Path::new(side);
+	if path.is_file() {
+		return LibhookerConfig::from_file(path);
+	}
+	match history.find(side)? {
+		Some(snapshot) => snapshot.load(),
+		None => {
+			eprintln!(
+				"'{}' is not a file or snapshot!\nUse `{}` to see a list of snapshots!",
+				side.red(),
+				"bender history".green()
+			);
+			std::process::exit(1);
+		}
+	}
+}
+
+fn on_off(value: Option<bool>) -> String {
+	match value {
+		Some(true) => "on".green().to_string(),
+		Some(false) => "off".red().to_string(),
+		None => "unset".dark_gray().to_string(),
+	}
+}
+
+/// Describe the value of a key we don't know about, briefly.
+fn unknown_value(value: Option<&Value>) -> String {
+	match value {
+		None => "unset".to_string(),
+		Some(Value::Boolean(value)) => value.to_string(),
+		Some(Value::String(value)) => format!("{:?}", value),
+		Some(Value::Integer(value)) => value.to_string(),
+		Some(Value::Real(value)) => value.to_string(),
+		Some(Value::Array(array)) => format!("an array of {}", array.len()),
+		Some(Value::Dictionary(dict)) => format!("a dictionary of {}", dict.len()),
+		Some(Value::Data(data)) => format!("{} bytes of data", data.len()),
+		Some(value) => format!("{:?}", value),
+	}
+}
+
+fn compat_mode(compat_mode: bool) -> String {
+	if compat_mode == COMPAT_LIBHOOKER {
+		"libhooker default".blue().to_string()
+	} else {
+		"substrate compatibility".magenta().to_string()
+	}
+}
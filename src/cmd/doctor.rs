@@ -0,0 +1,59 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::cmd::output::{self, Record};
+use bender_core::doctor::{self, Check, Status};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+
+impl Record for Check {
+	fn tsv(&self) -> Vec<String> {
+		vec![
+			self.name.to_string(),
+			status_name(self.status).to_string(),
+			self.detail.clone(),
+			output::optional(self.fix.as_ref()),
+		]
+	}
+}
+
+pub fn doctor() -> Result<()> {
+	let checks = doctor::run();
+	let healthy = checks.iter().all(|check| check.status != Status::Error);
+	if !output::is_text() {
+		output::print_records(&checks)?;
+	} else {
+		for check in &checks {
+			let marker = match check.status {
+				Status::Ok => "✓".green(),
+				Status::Warning => "!".yellow(),
+				Status::Error => "✗".red(),
+			};
+			println!("{} {}: {}", marker, check.name.bold(), check.detail);
+			if let Some(fix) = &check.fix {
+				println!("    {}", fix.as_str().dark_gray());
+			}
+		}
+		if healthy {
+			println!("Everything bender needs is {}!", "ready".green());
+		}
+	}
+	if !healthy {
+		std::process::exit(1);
+	}
+	Ok(())
+}
+
+fn status_name(status: Status) -> &'static str {
+	match status {
+		Status::Ok => "ok",
+		Status::Warning => "warning",
+		Status::Error => "error",
+	}
+}
@@ -0,0 +1,180 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	cmd::output::{self, Record},
+	providers::Providers,
+};
+use bender_core::{
+	explain::{explain as explain_tweak, Explanation, Scope, Step},
+	filter::Process,
+	libhooker::{LibhookerConfig, Target, TweakMode},
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+
+pub fn explain(
+	config: LibhookerConfig,
+	providers: &Providers,
+	target: Target,
+	tweaks: Vec<String>,
+) -> Result<()> {
+	let target_name = target.display(providers.apps.as_ref()).to_string();
+	let process = Process::for_target(&target, providers.apps.as_ref())?;
+	// If no tweaks were given, explain every installed tweak.
+	let tweaks = if tweaks.is_empty() {
+		providers.tweaks.tweaks()?.to_vec()
+	} else {
+		tweaks
+	};
+
+	if !output::is_text() {
+		let explanations = tweaks
+			.iter()
+			.map(|tweak| {
+				explain_tweak(
+					&config.tweak_configs,
+					&target,
+					process.as_ref(),
+					tweak,
+					providers.tweaks.filter(tweak),
+				)
+			})
+			.collect::<Vec<Explanation>>();
+		return output::print_records(&explanations);
+	}
+
+	println!("Explaining tweaks for {}", target_name.as_str().yellow());
+	for tweak in tweaks {
+		let filter = providers.tweaks.filter(&tweak);
+		let explanation = explain_tweak(
+			&config.tweak_configs,
+			&target,
+			process.as_ref(),
+			&tweak,
+			filter,
+		);
+		let readable_tweak_name = tweak.strip_suffix(".dylib").unwrap_or(&tweak);
+		println!(
+			"{}: {}",
+			readable_tweak_name,
+			if explanation.loads {
+				"WILL LOAD".light_green()
+			} else {
+				"WILL NOT LOAD".red()
+			}
+		);
+		// We need to remember the mode to explain what a tweak's entry means.
+		let mut mode = TweakMode::default();
+		for step in explanation.steps {
+			let (passed, reason) = match step {
+				Step::NoFilter => (
+					None,
+					format!(
+						"{} has no filter plist, so we can't tell if it's injected here",
+						readable_tweak_name
+					),
+				),
+				Step::Filter { filter, matches } => (
+					Some(matches),
+					format!(
+						"filter {} this process (injects into {})",
+						if matches {
+							"includes"
+						} else {
+							"doesn't include"
+						},
+						filter
+					),
+				),
+				Step::NoTargetConfig => (
+					None,
+					format!(
+						"{} has no configuration, so the default configuration applies",
+						target_name
+					),
+				),
+				Step::EnableTweaks { scope, enabled } => (
+					Some(enabled),
+					format!(
+						"tweak loading is {} in {}",
+						if enabled { "on" } else { "off" },
+						describe_scope(scope)
+					),
+				),
+				Step::CustomConfig { enabled } => (
+					None,
+					if enabled {
+						"custom configuration is on, so the target's own configuration applies"
+							.to_string()
+					} else {
+						"custom configuration is off, so the default configuration applies"
+							.to_string()
+					},
+				),
+				Step::Mode {
+					scope,
+					mode: scope_mode,
+				} => {
+					mode = scope_mode;
+					(
+						None,
+						format!("{} is in {} mode", describe_scope(scope), scope_mode),
+					)
+				}
+				Step::Entry { value, .. } => (
+					Some(mode.check(value)),
+					format!(
+						"{} is set \"{}\", which {} it",
+						readable_tweak_name,
+						if value { "on" } else { "off" },
+						if mode.check(value) {
+							"allows"
+						} else {
+							"denies"
+						}
+					),
+				),
+				Step::MissingEntry { .. } => (
+					Some(mode.check(false)),
+					format!(
+						"{} has no entry, which libhooker reads as \"off\", which {} it",
+						readable_tweak_name,
+						if mode.check(false) {
+							"allows"
+						} else {
+							"denies"
+						}
+					),
+				),
+			};
+			let marker = match passed {
+				Some(true) => "✓".green(),
+				Some(false) => "✗".red(),
+				None => "·".dark_gray(),
+			};
+			println!("  {} {}", marker, reason);
+		}
+	}
+
+	Ok(())
+}
+
+impl Record for Explanation {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.tweak.clone(), self.loads.to_string()]
+	}
+}
+
+fn describe_scope(scope: Scope) -> &'static str {
+	match scope {
+		Scope::Target => "the target's configuration",
+		Scope::Default => "the default configuration",
+	}
+}
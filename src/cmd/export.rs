@@ -0,0 +1,69 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{output, SaveOpts};
+use crate::providers::Providers;
+use bender_core::{
+	export::{self, Unknown},
+	libhooker::LibhookerConfig,
+	store::ConfigFile,
+};
+use color_eyre::eyre::{eyre, Result};
+use colorful::Colorful;
+use std::path::Path;
+
+pub fn export(config: &LibhookerConfig) -> Result<()> {
+	let format = output::format()
+		.export_format()
+		.ok_or_else(|| eyre!("a configuration can't be exported as TSV"))?;
+	print!("{}", export::export(config, format)?);
+	Ok(())
+}
+
+pub fn import(
+	config_file: &mut ConfigFile,
+	config: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+	file: &Path,
+	force: bool,
+) -> Result<()> {
+	let imported = export::import(file)?;
+
+	// Make sure we're not about to configure things that don't exist.
+	let unknown = Unknown::find(
+		&imported,
+		providers.tweaks.as_ref(),
+		providers.apps.as_ref(),
+	)?;
+	for tweak in &unknown.tweaks {
+		eprintln!(
+			"{} {} is not installed",
+			"unknown tweak".red(),
+			tweak.as_str().yellow()
+		);
+	}
+	for bundle in &unknown.bundles {
+		eprintln!(
+			"{} {} is not installed",
+			"unknown app".red(),
+			bundle.as_str().yellow()
+		);
+	}
+	if !unknown.is_empty() && !force {
+		eprintln!(
+			"Not importing {}! Use `{}` to import it anyways.",
+			file.display().to_string().red(),
+			"--force".green()
+		);
+		std::process::exit(1);
+	}
+
+	super::save(config_file, config, &imported, providers, opts)
+}
@@ -0,0 +1,167 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{
+	output::{self, Record},
+	SaveOpts,
+};
+use crate::providers::Providers;
+use bender_core::{
+	diff::diff,
+	history::{History, Snapshot},
+	libhooker::LibhookerConfig,
+	store::ConfigFile,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use serde::Serialize;
+
+/// A snapshot, for machine-readable output.
+#[derive(Serialize)]
+struct SnapshotRecord {
+	id: String,
+	date: String,
+	summary: String,
+}
+
+impl Record for SnapshotRecord {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.id.clone(), self.date.clone(), self.summary.clone()]
+	}
+}
+
+pub fn history(history: &History) -> Result<()> {
+	let snapshots = history.snapshots()?;
+	if !output::is_text() {
+		let records = snapshots
+			.into_iter()
+			.map(|snapshot| SnapshotRecord {
+				date: snapshot.date(),
+				id: snapshot.id,
+				summary: snapshot.summary,
+			})
+			.collect::<Vec<SnapshotRecord>>();
+		return output::print_records(&records);
+	}
+	if snapshots.is_empty() {
+		println!("bender hasn't changed anything yet!");
+		return Ok(());
+	}
+	for snapshot in snapshots {
+		println!(
+			"{} ({}) before {}",
+			snapshot.id.as_str().yellow(),
+			snapshot.date().cyan(),
+			snapshot.summary.as_str().green()
+		);
+	}
+	Ok(())
+}
+
+pub fn restore(
+	history: &History,
+	config_file: &mut ConfigFile,
+	providers: &Providers,
+	opts: SaveOpts,
+	id: &str,
+) -> Result<()> {
+	let snapshot = match history.find(id)? {
+		Some(snapshot) => snapshot,
+		None => {
+			eprintln!(
+				"Snapshot '{}' not found!\nUse `{}` to see a list of snapshots!",
+				id.red(),
+				"bender history".green()
+			);
+			std::process::exit(1);
+		}
+	};
+	if output::is_text() {
+		println!(
+			"Restoring the configuration from {}",
+			snapshot.date().cyan()
+		);
+	}
+	let summary = super::summary(&format!("restored {}", snapshot.id));
+	if roll_back(config_file, &snapshot, providers, opts, Some(&summary))? {
+		super::remind_to_respring();
+	}
+	Ok(())
+}
+
+pub fn undo(
+	history: &History,
+	config_file: &mut ConfigFile,
+	providers: &Providers,
+	opts: SaveOpts,
+) -> Result<()> {
+	let snapshot = match history.latest()? {
+		Some(snapshot) => snapshot,
+		None => {
+			if output::is_text() {
+				println!("There's nothing to undo!");
+			} else {
+				// Nothing to undo is the same as undoing nothing.
+				let config = LibhookerConfig::default();
+				super::confirm(&config, &config, providers, opts)?;
+			}
+			return Ok(());
+		}
+	};
+	if output::is_text() {
+		println!("Undoing {}", snapshot.summary.as_str().green());
+	}
+	// An undo isn't saved to the history, so that undoing again goes further back,
+	// rather than undoing the undo.
+	if roll_back(config_file, &snapshot, providers, opts, None)? {
+		snapshot.remove()?;
+		super::remind_to_respring();
+	} else if !opts.dry_run && unchanged(config_file, &snapshot)? {
+		// If the snapshot is the same as the current configuration, there's nothing to undo,
+		// and it's not worth keeping around either.
+		snapshot.remove()?;
+	}
+	Ok(())
+}
+
+/// Replace the configuration with a snapshot, byte for byte.
+/// The current configuration doesn't have to be readable, since a broken one is usually why it's being rolled back.
+/// Returns whether anything was written.
+fn roll_back(
+	config_file: &mut ConfigFile,
+	snapshot: &Snapshot,
+	providers: &Providers,
+	opts: SaveOpts,
+	summary: Option<&str>,
+) -> Result<bool> {
+	let restored = snapshot.load()?;
+	let proceed = match config_file.load_raw()? {
+		None => super::confirm(&LibhookerConfig::default(), &restored, providers, opts)?,
+		Some(bytes) => match LibhookerConfig::from_bytes(&bytes) {
+			Ok(current) => super::confirm(&current, &restored, providers, opts)?,
+			Err(err) => super::confirm_replace(&format!("{:#}", err), opts)?,
+		},
+	};
+	if proceed {
+		config_file.save_raw(&snapshot.bytes()?, summary)?;
+	}
+	Ok(proceed)
+}
+
+/// Whether a snapshot is the same as the current configuration.
+fn unchanged(config_file: &mut ConfigFile, snapshot: &Snapshot) -> Result<bool> {
+	let current = match config_file.load_raw()? {
+		Some(bytes) => match LibhookerConfig::from_bytes(&bytes) {
+			Ok(current) => current,
+			Err(_) => return Ok(false),
+		},
+		None => LibhookerConfig::default(),
+	};
+	Ok(diff(&current, &snapshot.load()?).is_empty())
+}
@@ -0,0 +1,150 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{
+	output::{self, Record},
+	SaveOpts,
+};
+use crate::providers::Providers;
+use bender_core::{
+	libhooker::{LibhookerConfig, Target},
+	lint::{lint as lint_config, Issue, Severity},
+	store::ConfigFile,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use serde::Serialize;
+
+/// An issue, for machine-readable output.
+#[derive(Serialize)]
+struct IssueRecord<'a> {
+	severity: Severity,
+	fixable: bool,
+	#[serde(flatten)]
+	issue: &'a Issue,
+}
+
+impl Record for IssueRecord<'_> {
+	fn tsv(&self) -> Vec<String> {
+		vec![
+			severity_name(self.severity).to_string(),
+			self.fixable.to_string(),
+			describe(self.issue, None),
+		]
+	}
+}
+
+pub fn lint(
+	config_file: &mut ConfigFile,
+	config: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+	fix: bool,
+) -> Result<()> {
+	let issues = lint_config(
+		config,
+		providers.tweaks.as_ref(),
+		providers.apps.as_ref(),
+		providers.services.as_ref(),
+	)?;
+	// Only warnings and errors are worth failing over.
+	let failing = |issue: &&Issue| issue.severity() >= Severity::Warning;
+
+	// When fixing, a script gets the changes that were made, rather than the issues.
+	if !output::is_text() && !fix {
+		let records = issues
+			.iter()
+			.map(|issue| IssueRecord {
+				severity: issue.severity(),
+				fixable: issue.fixable(),
+				issue,
+			})
+			.collect::<Vec<IssueRecord>>();
+		output::print_records(&records)?;
+	} else if output::is_text() {
+		if issues.is_empty() {
+			println!("No problems found!");
+			return Ok(());
+		}
+		for issue in &issues {
+			let severity = match issue.severity() {
+				Severity::Info => "info".blue(),
+				Severity::Warning => "warning".yellow(),
+				Severity::Error => "error".red(),
+			};
+			println!("{}: {}", severity, describe(issue, Some(providers)));
+		}
+	}
+
+	if fix {
+		let mut fixed = config.clone();
+		for issue in &issues {
+			if !issue.fix(&mut fixed) && output::is_text() {
+				println!(
+					"{} {}",
+					"Can't fix:".red(),
+					describe(issue, Some(providers))
+				);
+			}
+		}
+		super::save(config_file, config, &fixed, providers, opts)?;
+		if issues.iter().filter(failing).any(|issue| !issue.fixable()) {
+			std::process::exit(1);
+		}
+	} else if issues.iter().any(|issue| failing(&issue)) {
+		std::process::exit(1);
+	}
+	Ok(())
+}
+
+/// Describe an issue in plain English. With providers, targets are shown by their friendly names.
+fn describe(issue: &Issue, providers: Option<&Providers>) -> String {
+	let name = |target: &Target| match providers {
+		Some(providers) => target
+			.display(providers.apps.as_ref())
+			.to_string()
+			.yellow()
+			.to_string(),
+		None => target.key(),
+	};
+	match issue {
+		Issue::UninstalledTweak { target, tweak } => format!(
+			"{} has an entry for {}, which isn't installed",
+			name(target),
+			tweak
+		),
+		Issue::MissingApp { target } => {
+			format!("{} is configured, but isn't installed", name(target))
+		}
+		Issue::MissingExecutable { target } => {
+			format!("{} is configured, but doesn't exist", name(target))
+		}
+		Issue::DuplicateTarget { app, executable } => format!(
+			"{} is configured both by it's bundle ID and by it's executable, {}",
+			name(app),
+			executable.key()
+		),
+		Issue::IgnoredTweaks { target, count } => format!(
+			"{} has {} tweak entries, but libhooker ignores them since custom configuration is off",
+			name(target),
+			count
+		),
+		Issue::UninstalledCompat { tweak } => {
+			format!("{} has a compatibility mode, but isn't installed", tweak)
+		}
+	}
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+	match severity {
+		Severity::Info => "info",
+		Severity::Warning => "warning",
+		Severity::Error => "error",
+	}
+}
@@ -8,25 +8,109 @@
 */
 
 use crate::{
-	cmd::CmdList,
+	cmd::{
+		output::{self, Record},
+		resolve_target, CmdList,
+	},
+	providers::Providers,
+};
+use bender_core::{
+	apps::App,
+	daemons::Daemon,
+	filter::{Process, TweakFilter},
 	libhooker::{LibhookerConfig, COMPAT_LIBHOOKER},
-	APPS, DAEMONS, TWEAKS,
+	spec::CompatMode,
 };
 use color_eyre::eyre::Result;
 use colorful::Colorful;
-use std::ffi::OsStr;
+use serde::Serialize;
+use std::path::Path;
+
+/// A tweak, for machine-readable output.
+#[derive(Serialize)]
+struct TweakRecord<'a> {
+	/// The file name of the tweak, such as `Example.dylib`.
+	name: &'a str,
+	/// Whether the default configuration loads the tweak.
+	loads_by_default: bool,
+	compat: CompatMode,
+	filter: Option<TweakFilter>,
+}
+
+impl Record for TweakRecord<'_> {
+	fn tsv(&self) -> Vec<String> {
+		vec![
+			self.name.to_string(),
+			self.loads_by_default.to_string(),
+			self.compat.name().to_string(),
+		]
+	}
+}
+
+impl Record for App {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.identifier.clone(), self.name.clone()]
+	}
+}
 
-pub fn list(what: CmdList, cfg: LibhookerConfig) -> Result<()> {
-	assert!(std::path::PathBuf::from("/.procursus_strapped").is_file());
+/// A daemon, for machine-readable output.
+#[derive(Serialize)]
+struct DaemonRecord<'a> {
+	label: &'a str,
+	name: Option<&'a str>,
+	path: &'a Path,
+}
+
+impl Record for DaemonRecord<'_> {
+	fn tsv(&self) -> Vec<String> {
+		vec![
+			self.label.to_string(),
+			output::optional(self.name),
+			self.path.display().to_string(),
+		]
+	}
+}
+
+pub fn list(what: CmdList, cfg: LibhookerConfig, providers: &Providers) -> Result<()> {
 	let default = &cfg.tweak_configs.default;
 	match what {
+		CmdList::Apps if !output::is_text() => output::print_records(providers.apps.apps()?)?,
 		CmdList::Apps => {
-			for app in APPS.iter() {
+			for app in providers.apps.apps()? {
 				println!("{} [{}]", app.name, app.identifier.as_str().dark_gray());
 			}
 		}
-		CmdList::Tweaks => {
-			for tweak_name in TWEAKS.iter() {
+		CmdList::Tweaks { for_target } => {
+			// If we're only showing tweaks for a certain target, figure out what process that is.
+			let process = match for_target {
+				Some(target) => {
+					let target = resolve_target(&target, providers)?;
+					Process::for_target(&target, providers.apps.as_ref())?
+				}
+				None => None,
+			};
+			let mut records = Vec::new();
+			for tweak_name in providers.tweaks.tweaks()? {
+				let filter = providers.tweaks.filter(tweak_name);
+				if let (Some(process), Some(filter)) = (&process, &filter) {
+					if !filter.matches(process) {
+						continue;
+					}
+				}
+				if !output::is_text() {
+					records.push(TweakRecord {
+						name: tweak_name,
+						loads_by_default: default.will_tweak_load(tweak_name),
+						compat: cfg
+							.memory_compat_prefs
+							.get(tweak_name)
+							.copied()
+							.unwrap_or(COMPAT_LIBHOOKER)
+							.into(),
+						filter,
+					});
+					continue;
+				}
 				let readable_tweak_name = tweak_name
 					.strip_suffix(".dylib")
 					.map(|x| x.to_string())
@@ -34,7 +118,7 @@ pub fn list(what: CmdList, cfg: LibhookerConfig) -> Result<()> {
 				println!(
 					"{}: {} by default{}",
 					readable_tweak_name,
-					if default.will_tweak_load(&tweak_name) {
+					if default.will_tweak_load(tweak_name) {
 						"ENABLED".light_green()
 					} else {
 						"DISABLED".red()
@@ -50,13 +134,34 @@ pub fn list(what: CmdList, cfg: LibhookerConfig) -> Result<()> {
 					} else {
 						format!(", with {}", "substrate compatibility mode".red())
 					}
-				)
+				);
+				if let Some(filter) = filter {
+					println!(" {} {}", "injects into".dark_gray(), filter);
+				}
+			}
+			if !output::is_text() {
+				output::print_records(&records)?;
 			}
 		}
+		CmdList::Daemons if !output::is_text() => {
+			let records = providers
+				.services
+				.daemons()?
+				.iter()
+				.map(|daemon| DaemonRecord {
+					label: &daemon.label,
+					name: daemon.name(),
+					path: &daemon.executable,
+				})
+				.collect::<Vec<DaemonRecord>>();
+			output::print_records(&records)?;
+		}
 		CmdList::Daemons => {
-			let mut daemons = DAEMONS
+			let mut daemons = providers
+				.services
+				.daemons()?
 				.iter()
-				.filter_map(|d| d.file_name().and_then(OsStr::to_str))
+				.filter_map(Daemon::name)
 				.collect::<Vec<&str>>();
 			daemons.sort_unstable();
 			for daemon in daemons {
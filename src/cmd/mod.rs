@@ -7,12 +7,37 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
+pub mod apply;
+pub mod clone;
 pub mod compat;
 pub mod config;
+pub mod diff;
+pub mod doctor;
+pub mod explain;
+pub mod export;
+pub mod history;
+pub mod lint;
 pub mod list;
+pub mod output;
+pub mod profile;
+pub mod reset;
+pub mod tweak;
 pub mod view;
+pub mod where_;
 
-use clap::{Clap, ValueHint};
+use crate::providers::Providers;
+use bender_core::{
+	diff::Change,
+	libhooker::{LibhookerConfig, Target},
+	store::ConfigFile,
+	tweaks,
+};
+use clap::{ArgEnum, Clap, ValueHint};
+use color_eyre::eyre::{bail, Result};
+use colorful::Colorful;
+use output::{OutputFormat, Record};
+use serde::Serialize;
+use std::{io::Write, path::PathBuf};
 
 fn parse_yes_no(s: &str) -> Result<bool, &'static str> {
 	match s.to_lowercase().trim() {
@@ -22,6 +47,170 @@ fn parse_yes_no(s: &str) -> Result<bool, &'static str> {
 	}
 }
 
+/// Resolve the target the user entered, or exit with an explanation if it isn't valid.
+pub fn resolve_target(input: &str, providers: &Providers) -> Result<Target> {
+	match Target::resolve(input, providers.apps.as_ref(), providers.services.as_ref())? {
+		Some(target) => Ok(target),
+		None => {
+			eprintln!(
+				r#"An {}, {}, was entered!
+It should either be an {}, {}, {}, "{}", or "{}"!"#,
+				"invalid target".red().bold(),
+				input.trim().red().underlined(),
+				"absolute path to an executable".cyan(),
+				"app name or bundle ID".magenta(),
+				"daemon or service name".blue(),
+				"springboard".yellow(),
+				"default".green()
+			);
+			std::process::exit(1);
+		}
+	}
+}
+
+/// Resolve the tweak the user entered, or exit with an explanation if it isn't installed.
+pub fn resolve_tweak(input: &str, providers: &Providers) -> Result<String> {
+	match tweaks::fix_tweak_name(providers.tweaks.as_ref(), input)? {
+		Some(tweak) => Ok(tweak),
+		None => {
+			eprintln!(
+				"Tweak '{}' not found!\nUse `{}` to see a list of available tweaks!",
+				input.red(),
+				"bender list tweaks".green()
+			);
+			std::process::exit(1);
+		}
+	}
+}
+
+/// Save the changed configuration, and remind the user to apply it.
+/// The command line, and what it changed, is used as the summary of the change in the history.
+pub fn save(
+	config_file: &mut ConfigFile,
+	before: &LibhookerConfig,
+	after: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+) -> Result<()> {
+	if !confirm(before, after, providers, opts)? {
+		return Ok(());
+	}
+	let changes = bender_core::diff::diff(before, after);
+	config_file.save(
+		after,
+		Some(&summary(&bender_core::diff::summarize(&changes))),
+	)?;
+	remind_to_respring();
+	Ok(())
+}
+
+/// The summary of a change for the history: the command line that made it, and what it did.
+pub fn summary(what: &str) -> String {
+	let command = std::iter::once("bender".to_string())
+		.chain(std::env::args().skip(1))
+		.collect::<Vec<String>>()
+		.join(" ");
+	format!("{} ({})", command, what)
+}
+
+/// Show what's about to change, and ask the user whether to go ahead with it.
+/// Returns false if nothing should be written, whether that's because nothing changed,
+/// it's a dry run, or the user said no.
+pub fn confirm(
+	before: &LibhookerConfig,
+	after: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+) -> Result<bool> {
+	let changes = bender_core::diff::diff(before, after);
+	if !output::is_text() {
+		return report(!changes.is_empty(), changes, opts);
+	}
+	if changes.is_empty() {
+		println!("Nothing to change!");
+		return Ok(false);
+	}
+	println!("{}", "Changes:".bold());
+	diff::print_changes(changes, providers);
+	ask(opts)
+}
+
+/// Ask whether to replace a configuration that can't be read at all, so there's nothing to compare against.
+/// Returns false if nothing should be written, just like [confirm].
+pub fn confirm_replace(reason: &str, opts: SaveOpts) -> Result<bool> {
+	if !output::is_text() {
+		return report(true, Vec::new(), opts);
+	}
+	println!(
+		"The current configuration {}, so it'll be replaced entirely: {}",
+		"can't be read".red(),
+		reason
+	);
+	ask(opts)
+}
+
+/// Print what's going to change for a script, which has to say up front that it's okay to change things,
+/// since there's nobody to ask.
+fn report(changing: bool, changes: Vec<Change>, opts: SaveOpts) -> Result<bool> {
+	if changing && !opts.dry_run && !opts.yes {
+		bail!(
+			"use --yes (or --dry-run) to change the configuration with --format {}",
+			output::format()
+		);
+	}
+	let applied = changing && !opts.dry_run;
+	output::print_record(&SaveReport { applied, changes })?;
+	Ok(applied)
+}
+
+/// Ask the user whether to go ahead, unless it's a dry run, or they've already said yes.
+fn ask(opts: SaveOpts) -> Result<bool> {
+	if opts.dry_run {
+		println!("This is a {}, so nothing was changed.", "dry run".yellow());
+		return Ok(false);
+	}
+	if opts.yes {
+		return Ok(true);
+	}
+
+	print!("Apply these changes? [y/N] ");
+	std::io::stdout().flush()?;
+	let mut answer = String::new();
+	std::io::stdin().read_line(&mut answer)?;
+	// Anything that isn't a yes (including no answer at all) is a no.
+	if parse_yes_no(&answer).unwrap_or(false) {
+		Ok(true)
+	} else {
+		println!("Okay, nothing was changed.");
+		Ok(false)
+	}
+}
+
+pub fn remind_to_respring() {
+	if !output::is_text() {
+		return;
+	}
+	println!(
+		"Ensure to {} or {} your device to apply the changes!",
+		"respring".yellow(),
+		"userspace reboot".magenta()
+	);
+}
+
+/// What a command that changes the configuration did, for machine-readable output.
+#[derive(Serialize)]
+struct SaveReport {
+	/// Whether the changes were written. They aren't on a dry run, or if there weren't any.
+	applied: bool,
+	changes: Vec<Change>,
+}
+
+impl Record for SaveReport {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.applied.to_string(), self.changes.len().to_string()]
+	}
+}
+
 #[derive(Clap, Debug)]
 #[clap(
 	author,
@@ -50,7 +239,60 @@ copyright (C) 2021, aspen
 All rights reserved.
 This software may only be used in situations where the use of libhooker is permitted."#
 )]
-pub enum CmdOpts {
+pub struct CmdOpts {
+	/// Treat this directory as the root of the device's filesystem,
+	/// such as an extracted filesystem dump.
+	#[clap(long, global = true, env = "BENDER_ROOT", default_value = "/", value_hint = ValueHint::DirPath)]
+	pub root: PathBuf,
+	/// Read the installed tweaks, apps, and daemons from this plist manifest,
+	/// rather than from the device itself.
+	#[clap(long, global = true, env = "BENDER_MANIFEST", value_hint = ValueHint::FilePath)]
+	pub manifest: Option<PathBuf>,
+	/// Where to get the list of installed apps from: "workspace" asks iOS itself,
+	/// "bundles" reads the Info.plist of every app bundle on the filesystem.
+	/// Defaults to "workspace" on a live device, and "bundles" otherwise.
+	#[clap(long, global = true, arg_enum)]
+	pub apps_from: Option<AppSource>,
+	/// Where to get the list of daemons from: "launchctl" asks launchd about the loaded daemons,
+	/// "plists" reads every plist in the LaunchDaemons directories.
+	/// Defaults to "launchctl" on a live device, and "plists" otherwise.
+	#[clap(long, global = true, arg_enum)]
+	pub daemons_from: Option<DaemonSource>,
+	/// How to print the output: "text" for humans, or "json" or "tsv" for scripts.
+	/// `bender export` also takes "yaml", "toml", and "xml-plist".
+	#[clap(long, global = true, default_value = "text", possible_values = &["text", "json", "tsv", "yaml", "toml", "xml-plist"])]
+	pub format: OutputFormat,
+	#[clap(flatten)]
+	pub save: SaveOpts,
+	#[clap(subcommand)]
+	pub cmd: Cmd,
+}
+
+/// Options for commands that change the configuration.
+#[derive(Clap, Debug, Clone, Copy)]
+pub struct SaveOpts {
+	/// Show what would change, without actually changing anything.
+	#[clap(long, global = true)]
+	pub dry_run: bool,
+	/// Don't ask before changing anything.
+	#[clap(short, long, global = true)]
+	pub yes: bool,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq)]
+pub enum AppSource {
+	Workspace,
+	Bundles,
+}
+
+#[derive(ArgEnum, Debug, Clone, Copy, PartialEq)]
+pub enum DaemonSource {
+	Launchctl,
+	Plists,
+}
+
+#[derive(Clap, Debug)]
+pub enum Cmd {
 	/// List tweaks, configurations, et cetera
 	List(CmdListShim),
 	/// Configure libhooker.
@@ -61,6 +303,10 @@ pub enum CmdOpts {
 		/// Change the tweak loader to deny loading selected tweaks.
 		#[clap(long, group = "allowdeny")]
 		deny: bool,
+		/// When switching between allowlist and denylist mode, keep every entry as it is,
+		/// which flips which tweaks load. Without this, the entries are rewritten so the same tweaks keep loading.
+		#[clap(long, requires = "allowdeny")]
+		invert: bool,
 		/// Whether to enable custom configuration or not for the target (true/on/yes/y/false/off/no/n)
 		#[clap(long, parse(try_from_str = parse_yes_no), alias = "custom")]
 		custom_config: Option<bool>,
@@ -79,6 +325,12 @@ pub enum CmdOpts {
 		/// You can put "all" here to set all tweaks "off".
 		#[clap(short, long, alias = "disable")]
 		remove: Vec<String>,
+		/// Remove these tweaks' entries from the configuration entirely.
+		/// libhooker reads a tweak without an entry as "off", it isn't inherited from the default configuration.
+		/// You can put "all" here to remove every entry, even for tweaks that aren't installed anymore.
+		/// A tweak can't be in both this and --add or --remove.
+		#[clap(short, long)]
+		unset: Vec<String>,
 		/// The target to configure. Either an app bundle,
 		/// executable path, daemon/service name, "default",
 		///or "springboard".
@@ -93,7 +345,106 @@ pub enum CmdOpts {
 		/// Leave blank to see all currently set configurations.
 		#[clap(value_hint = ValueHint::ExecutablePath)]
 		target: Option<String>,
+		/// Show the configuration libhooker actually uses, with the default configuration merged in,
+		/// and every installed tweak marked as set here, inherited from the default, or unset.
+		#[clap(long)]
+		effective: bool,
+	},
+	/// Explain whether each tweak will load into a target, and why.
+	Explain {
+		/// The target to explain. Either an app bundle, executable path,
+		/// daemon/service name, "default", or "springboard".
+		#[clap(value_hint = ValueHint::ExecutablePath)]
+		target: String,
+		/// Only explain these tweaks, rather than every installed tweak.
+		tweaks: Vec<String>,
 	},
+	/// List every target a tweak has an entry in, and whether it loads into each of them.
+	Where {
+		/// The tweak to look for. It doesn't have to still be installed.
+		tweak: String,
+	},
+	/// Make the configuration match a TOML or YAML file describing how it should be.
+	Apply {
+		/// The file describing the configuration.
+		#[clap(value_hint = ValueHint::FilePath)]
+		file: PathBuf,
+		/// Don't change anything, just check whether the configuration matches the file,
+		/// exiting with an error if it doesn't.
+		#[clap(long)]
+		check: bool,
+	},
+	/// Show what's different between two configurations.
+	Diff {
+		/// The old configuration. Either "live" for the current configuration,
+		/// a snapshot ID from `bender history`, or the path to a libhooker plist.
+		old: String,
+		/// The new configuration, in the same form. Defaults to the current configuration.
+		#[clap(default_value = "live")]
+		new: String,
+	},
+	/// Print the configuration in a human-readable format:
+	/// JSON by default, or whichever of yaml, toml, or xml-plist `--format` says.
+	Export,
+	/// Replace the configuration with one that was exported with `bender export`, or any libhooker plist.
+	Import {
+		/// The file to import. Its format is guessed from the extension:
+		/// .json, .yaml, .yml, .toml, or .plist.
+		#[clap(value_hint = ValueHint::FilePath)]
+		file: PathBuf,
+		/// Import the configuration even if it mentions tweaks or apps that aren't installed.
+		#[clap(long)]
+		force: bool,
+	},
+	/// Save, switch between, and manage named configuration profiles.
+	Profile(CmdProfileShim),
+	/// List the snapshots of the configuration taken before each change, newest first.
+	History,
+	/// Roll the configuration back to a snapshot.
+	/// The current configuration is snapshotted first, so this can be undone too.
+	Restore {
+		/// The ID of the snapshot, from `bender history`.
+		/// The start of an ID is enough, as long as only one snapshot starts with it.
+		id: String,
+	},
+	/// Roll back the most recent change, removing it from the history.
+	/// Run it again to keep going back.
+	Undo,
+	/// Enable or disable tweaks, without having to think about allowlists and denylists.
+	Tweak(CmdTweakShim),
+	/// Find stale, conflicting, and pointless entries in the configuration,
+	/// exiting with an error if there are any warnings or errors.
+	Lint {
+		/// Remove the entries causing each problem, where that's possible.
+		#[clap(long)]
+		fix: bool,
+	},
+	/// Remove every entry that doesn't change what libhooker does, such as tweaks set "off",
+	/// and targets that are configured exactly like the default configuration.
+	Gc,
+	/// Remove a target's configuration, so it goes back to using the default configuration.
+	/// Resetting "default" puts it back to libhooker's defaults.
+	Reset {
+		/// The target to reset. Either an app bundle, executable path,
+		/// daemon/service name, "default", or "springboard".
+		#[clap(value_hint = ValueHint::ExecutablePath, required_unless_present = "all")]
+		target: Option<String>,
+		/// Reset every target, including the default configuration.
+		#[clap(long, conflicts_with = "target")]
+		all: bool,
+	},
+	/// Copy one target's whole configuration (mode, custom configuration, tweak loading, and tweak entries)
+	/// to other targets, replacing theirs.
+	Clone {
+		/// The target to copy the configuration of.
+		#[clap(value_hint = ValueHint::ExecutablePath)]
+		source: String,
+		/// The targets to copy the configuration to.
+		#[clap(value_hint = ValueHint::ExecutablePath, required = true)]
+		destinations: Vec<String>,
+	},
+	/// Check everything bender needs, and explain how to fix anything that's wrong.
+	Doctor,
 	/// Configure the compatibility mode for tweaks.
 	Compat {
 		/// Use the libhooker default compatibility mode for these tweaks.
@@ -115,11 +466,65 @@ pub struct CmdListShim {
 	pub cmd: CmdList,
 }
 
+#[derive(Clap, Debug)]
+pub struct CmdProfileShim {
+	#[clap(subcommand)]
+	pub cmd: CmdProfile,
+}
+
+#[derive(Clap, Debug)]
+pub enum CmdProfile {
+	/// Save the current tweak configurations and compatibility modes as a profile,
+	/// replacing any profile with the same name.
+	Save { name: String },
+	/// List the saved profiles.
+	#[clap(alias = "ls")]
+	List,
+	/// Switch the configuration to a saved profile.
+	Apply { name: String },
+	/// Delete a saved profile.
+	#[clap(alias = "rm")]
+	Delete { name: String },
+}
+
+#[derive(Clap, Debug)]
+pub struct CmdTweakShim {
+	#[clap(subcommand)]
+	pub cmd: CmdTweak,
+}
+
+#[derive(Clap, Debug)]
+pub enum CmdTweak {
+	/// Make tweaks load. Each entry is set "on" or "off",
+	/// depending on whether it's configuration is an allowlist or a denylist.
+	Enable {
+		#[clap(required = true)]
+		tweaks: Vec<String>,
+		/// Change every target's configuration, not just the default configuration.
+		#[clap(long)]
+		everywhere: bool,
+	},
+	/// Stop tweaks from loading. Each entry is set "on" or "off",
+	/// depending on whether it's configuration is an allowlist or a denylist.
+	Disable {
+		#[clap(required = true)]
+		tweaks: Vec<String>,
+		/// Change every target's configuration, not just the default configuration.
+		#[clap(long)]
+		everywhere: bool,
+	},
+}
+
 #[derive(Clap, Debug)]
 pub enum CmdList {
 	/// List the installed tweaks that can be enabled/disabled.
 	#[clap(alias = "tweak")]
-	Tweaks,
+	Tweaks {
+		/// Only show tweaks that would be injected into this target.
+		/// Either an app bundle, executable path, daemon/service name, or "springboard".
+		#[clap(long = "for", value_hint = ValueHint::ExecutablePath)]
+		for_target: Option<String>,
+	},
 	/// List the available app bundles.
 	#[clap(alias = "app")]
 	Apps,
@@ -0,0 +1,150 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use bender_core::export::Format;
+use color_eyre::eyre::{bail, Result};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::{fmt::Display, str::FromStr};
+
+/// How bender prints things: for humans, or for scripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+	Text,
+	Json,
+	Tsv,
+	// These three are only for `bender export`.
+	Yaml,
+	Toml,
+	XmlPlist,
+}
+
+impl FromStr for OutputFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().to_lowercase().as_str() {
+			"text" => Ok(Self::Text),
+			"json" => Ok(Self::Json),
+			"tsv" => Ok(Self::Tsv),
+			"yaml" | "yml" => Ok(Self::Yaml),
+			"toml" => Ok(Self::Toml),
+			"xml-plist" | "plist" | "xml" => Ok(Self::XmlPlist),
+			_ => Err("expected text, json, tsv, yaml, toml, or xml-plist".to_string()),
+		}
+	}
+}
+
+impl Display for OutputFormat {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			Self::Text => "text",
+			Self::Json => "json",
+			Self::Tsv => "tsv",
+			Self::Yaml => "yaml",
+			Self::Toml => "toml",
+			Self::XmlPlist => "xml-plist",
+		})
+	}
+}
+
+impl OutputFormat {
+	/// The format `bender export` should use. Text just means the default, which is JSON.
+	pub fn export_format(self) -> Option<Format> {
+		match self {
+			Self::Text | Self::Json => Some(Format::Json),
+			Self::Yaml => Some(Format::Yaml),
+			Self::Toml => Some(Format::Toml),
+			Self::XmlPlist => Some(Format::XmlPlist),
+			Self::Tsv => None,
+		}
+	}
+}
+
+// Just like the root, this is set once from `--format`, before anything else happens.
+static FORMAT: OnceCell<OutputFormat> = OnceCell::new();
+
+/// Set the output format. Only the first call has any effect.
+pub fn set_format(format: OutputFormat) {
+	let _ = FORMAT.set(format);
+}
+
+/// Get the output format, which is text by default.
+pub fn format() -> OutputFormat {
+	FORMAT.get().copied().unwrap_or(OutputFormat::Text)
+}
+
+/// Whether we're printing for humans. If not, the only thing on stdout should be records.
+pub fn is_text() -> bool {
+	format() == OutputFormat::Text
+}
+
+/// Something that can be printed as JSON, or as one line of TSV.
+pub trait Record: Serialize {
+	/// The columns of this record, in order.
+	fn tsv(&self) -> Vec<String>;
+}
+
+/// Print a list of records, as a JSON array or one TSV line per record.
+pub fn print_records<R: Record>(records: &[R]) -> Result<()> {
+	match format() {
+		// Nothing should ask for records as text, but if it does, JSON is at least readable.
+		OutputFormat::Text | OutputFormat::Json => {
+			println!("{}", serde_json::to_string_pretty(records)?)
+		}
+		OutputFormat::Tsv => {
+			for record in records {
+				print_tsv(record);
+			}
+		}
+		other => require_supported(other)?,
+	}
+	Ok(())
+}
+
+/// Print a single record, as a JSON object or one TSV line.
+pub fn print_record<R: Record>(record: &R) -> Result<()> {
+	match format() {
+		OutputFormat::Text | OutputFormat::Json => {
+			println!("{}", serde_json::to_string_pretty(record)?)
+		}
+		OutputFormat::Tsv => print_tsv(record),
+		other => require_supported(other)?,
+	}
+	Ok(())
+}
+
+fn print_tsv<R: Record>(record: &R) {
+	// Tabs and newlines would break up the columns and lines, so they're just spaces.
+	let columns = record
+		.tsv()
+		.into_iter()
+		.map(|column| column.replace(&['\t', '\n', '\r'][..], " "))
+		.collect::<Vec<String>>();
+	println!("{}", columns.join("\t"));
+}
+
+/// Make sure that a format is one every command can print, rather than one that's only for `bender export`.
+pub fn require_supported(format: OutputFormat) -> Result<()> {
+	if matches!(
+		format,
+		OutputFormat::Text | OutputFormat::Json | OutputFormat::Tsv
+	) {
+		return Ok(());
+	}
+	bail!(
+		"--format {} is only for `bender export`, use text, json, or tsv here",
+		format
+	)
+}
+
+/// A TSV column for a value that might not be set.
+pub fn optional<T: ToString>(value: Option<T>) -> String {
+	value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,106 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{
+	output::{self, Record},
+	CmdProfile, SaveOpts,
+};
+use crate::providers::Providers;
+use bender_core::{
+	libhooker::LibhookerConfig,
+	paths,
+	profiles::{Profile, Profiles},
+	store::ConfigFile,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use serde::Serialize;
+
+/// A saved profile, for machine-readable output.
+#[derive(Serialize)]
+struct ProfileRecord {
+	name: String,
+}
+
+impl Record for ProfileRecord {
+	fn tsv(&self) -> Vec<String> {
+		vec![self.name.clone()]
+	}
+}
+
+pub fn profile(
+	what: CmdProfile,
+	config_file: &mut ConfigFile,
+	config: &LibhookerConfig,
+	providers: &Providers,
+	opts: SaveOpts,
+) -> Result<()> {
+	let profiles = Profiles::new(paths::profiles_dir());
+	match what {
+		CmdProfile::Save { name } => {
+			profiles.save(&name, &Profile::from_config(config))?;
+			if output::is_text() {
+				println!("Saved the current configuration as {}", name.green());
+			} else {
+				output::print_record(&ProfileRecord { name })?;
+			}
+		}
+		CmdProfile::List => {
+			let names = profiles.list()?;
+			if !output::is_text() {
+				let records = names
+					.into_iter()
+					.map(|name| ProfileRecord { name })
+					.collect::<Vec<ProfileRecord>>();
+				return output::print_records(&records);
+			}
+			if names.is_empty() {
+				println!(
+					"There aren't any profiles yet! Use `{}` to save one.",
+					"bender profile save <name>".green()
+				);
+			}
+			for name in names {
+				println!("{}", name);
+			}
+		}
+		CmdProfile::Apply { name } => {
+			let profile = match profiles.load(&name)? {
+				Some(profile) => profile,
+				None => not_found(&name),
+			};
+			if output::is_text() {
+				println!("Switching to the {} profile", name.as_str().green());
+			}
+			let mut new_config = config.clone();
+			profile.apply_to(&mut new_config);
+			super::save(config_file, config, &new_config, providers, opts)?;
+		}
+		CmdProfile::Delete { name } => {
+			if !profiles.delete(&name)? {
+				not_found(&name);
+			}
+			if output::is_text() {
+				println!("Deleted the {} profile", name.red());
+			} else {
+				output::print_record(&ProfileRecord { name })?;
+			}
+		}
+	}
+	Ok(())
+}
+
+fn not_found(name: &str) -> ! {
+	eprintln!(
+		"Profile '{}' not found!\nUse `{}` to see a list of profiles!",
+		name.red(),
+		"bender profile list".green()
+	);
+	std::process::exit(1);
+}
@@ -0,0 +1,46 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{cmd::output, providers::Providers};
+use bender_core::libhooker::{LibhookerConfig, Target};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+
+/// Reset a target's configuration, or every target's if there's no target.
+pub fn reset(
+	config: &mut LibhookerConfig,
+	providers: &Providers,
+	target: Option<Target>,
+) -> Result<()> {
+	let target = match target {
+		Some(target) => target,
+		None => {
+			if output::is_text() {
+				println!("{} every configuration", "RESETTING".red());
+			}
+			config.tweak_configs.reset();
+			return Ok(());
+		}
+	};
+	let name = target.display(providers.apps.as_ref()).to_string();
+	if target.find_config(&config.tweak_configs).is_none() {
+		if output::is_text() {
+			println!(
+				"{} has no configuration of it's own, so there's nothing to reset",
+				name.yellow()
+			);
+		}
+		return Ok(());
+	}
+	if output::is_text() {
+		println!("{} {}", "RESETTING".red(), name.yellow());
+	}
+	target.remove_config(&mut config.tweak_configs);
+	Ok(())
+}
@@ -0,0 +1,82 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use super::{output, resolve_tweak, CmdTweak};
+use crate::providers::Providers;
+use bender_core::libhooker::{LibhookerConfig, Target};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+
+pub fn tweak(what: CmdTweak, config: &mut LibhookerConfig, providers: &Providers) -> Result<()> {
+	let (tweaks, load, everywhere) = match what {
+		CmdTweak::Enable { tweaks, everywhere } => (tweaks, true, everywhere),
+		CmdTweak::Disable { tweaks, everywhere } => (tweaks, false, everywhere),
+	};
+	let mut skipped = Vec::new();
+	for tweak in tweaks {
+		let tweak = resolve_tweak(&tweak, providers)?;
+		let readable_tweak_name = tweak.strip_suffix(".dylib").unwrap_or(&tweak);
+		if output::is_text() {
+			println!(
+				"{} {} in {}",
+				if load {
+					"ENABLING".green()
+				} else {
+					"DISABLING".red()
+				},
+				readable_tweak_name,
+				if everywhere {
+					"every configuration"
+				} else {
+					"the default configuration"
+				}
+			);
+		}
+		for target in config.set_tweak_loads(&tweak, load, everywhere) {
+			if !skipped.contains(&target) {
+				skipped.push(target);
+			}
+		}
+	}
+
+	if output::is_text() {
+		for target in &skipped {
+			println!(
+				"{} has {} off, so libhooker would ignore it's entries, and it was left alone",
+				target.display(providers.apps.as_ref()).to_string().yellow(),
+				"custom configuration".blue()
+			);
+		}
+	}
+
+	// The entry can't do anything about a target with tweak loading turned off entirely.
+	if load && everywhere && output::is_text() {
+		let configs = &config.tweak_configs;
+		let disabled = configs
+			.paths
+			.iter()
+			.filter(|(_, tweak_config)| !tweak_config.enable_tweaks)
+			.map(|(path, _)| Target::Executable(path.clone()))
+			.chain(
+				configs
+					.bundles
+					.iter()
+					.filter(|(_, tweak_config)| !tweak_config.enable_tweaks)
+					.map(|(bundle, _)| Target::App(bundle.clone())),
+			);
+		for target in disabled {
+			println!(
+				"{} has {} off, so nothing will load into it anyways",
+				target.display(providers.apps.as_ref()).to_string().yellow(),
+				"tweak loading".blue()
+			);
+		}
+	}
+	Ok(())
+}
@@ -7,17 +7,100 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
-use crate::libhooker::{LibhookerConfig, Target};
+use crate::{
+	cmd::output::{self, Record},
+	providers::Providers,
+};
+use bender_core::{
+	explain::{effective as effective_config, Effective, Origin, Scope},
+	libhooker::{serialize_mode_name, LibhookerConfig, Target, TweakMode},
+};
 use color_eyre::eyre::Result;
 use colorful::Colorful;
-use std::fmt::Write;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write};
 
-pub fn view(mut config: LibhookerConfig, target: Option<Target>) -> Result<()> {
-	assert!(std::path::PathBuf::from("/.procursus_strapped").is_file());
+/// A target's full configuration, for machine-readable output.
+#[derive(Serialize)]
+struct TargetRecord {
+	target: Target,
+	/// The name of the target, as it's shown to humans.
+	name: String,
+	custom_config: bool,
+	enable_tweaks: bool,
+	#[serde(serialize_with = "serialize_mode_name")]
+	mode: TweakMode,
+	/// Every tweak entry, as it's set in the configuration (not whether it's loaded).
+	tweaks: BTreeMap<String, bool>,
+}
+
+impl Record for TargetRecord {
+	fn tsv(&self) -> Vec<String> {
+		// The tweaks that are set "on", since that's what the mode applies to.
+		let tweaks = self
+			.tweaks
+			.iter()
+			.filter(|(_, value)| **value)
+			.map(|(tweak, _)| tweak.as_str())
+			.collect::<Vec<&str>>()
+			.join(",");
+		vec![
+			self.target.key(),
+			self.name.clone(),
+			self.custom_config.to_string(),
+			self.enable_tweaks.to_string(),
+			self.mode.name().to_string(),
+			tweaks,
+		]
+	}
+}
+
+/// A target's effective configuration, for machine-readable output.
+#[derive(Serialize)]
+struct EffectiveRecord {
+	target: Target,
+	name: String,
+	#[serde(flatten)]
+	effective: Effective,
+}
+
+impl Record for EffectiveRecord {
+	fn tsv(&self) -> Vec<String> {
+		let loaded = self
+			.effective
+			.tweaks
+			.iter()
+			.filter(|tweak| tweak.loads)
+			.map(|tweak| tweak.tweak.as_str())
+			.collect::<Vec<&str>>()
+			.join(",");
+		vec![
+			self.target.key(),
+			self.name.clone(),
+			self.effective.enable_tweaks.to_string(),
+			match self.effective.scope {
+				Scope::Target => "target",
+				Scope::Default => "default",
+			}
+			.to_string(),
+			self.effective.mode.name().to_string(),
+			loaded,
+		]
+	}
+}
+
+pub fn view(
+	mut config: LibhookerConfig,
+	providers: &Providers,
+	target: Option<Target>,
+	effective: bool,
+) -> Result<()> {
 	let tweak_cfg = &mut config.tweak_configs;
 	let targets: Vec<Target> = target.map(|x| vec![x]).unwrap_or_else(|| {
+		// Every target with a configuration is listed, even if it has no entries,
+		// since allowlist mode or turning tweak loading off matter just as much as entries do.
 		let mut targets = Vec::new();
-		if !tweak_cfg.default.should_skip() {
+		if !tweak_cfg.default.is_unset() {
 			targets.push(Target::Default);
 		}
 		targets.extend(
@@ -31,14 +114,40 @@ pub fn view(mut config: LibhookerConfig, target: Option<Target>) -> Result<()> {
 						.iter()
 						.map(|(bundle, cfg)| (Target::App(bundle.clone()), cfg)),
 				)
-				.filter(|(_, cfg)| !cfg.should_skip())
+				.filter(|(_, cfg)| !cfg.is_unset())
 				.map(|(target, _)| target),
 		);
 		targets
 	});
 
+	if effective {
+		return view_effective(&config, providers, targets);
+	}
+
+	if !output::is_text() {
+		let records = targets
+			.into_iter()
+			.map(|target| {
+				let name = target.display(providers.apps.as_ref()).to_string();
+				let cfg = target.get_config(tweak_cfg);
+				TargetRecord {
+					name,
+					custom_config: cfg.custom_config,
+					enable_tweaks: cfg.enable_tweaks,
+					mode: cfg.allow_or_deny,
+					tweaks: cfg.tweak_configs.clone(),
+					target,
+				}
+			})
+			.collect::<Vec<TargetRecord>>();
+		return output::print_records(&records);
+	}
+
 	for target in targets {
-		let mut output = format!("configuration for {}\n", target.to_string().yellow());
+		let mut output = format!(
+			"configuration for {}\n",
+			target.display(providers.apps.as_ref()).to_string().yellow()
+		);
 		let cfg = target.get_config(tweak_cfg);
 		writeln!(
 			output,
@@ -66,18 +175,110 @@ pub fn view(mut config: LibhookerConfig, target: Option<Target>) -> Result<()> {
 			for (tweak, state) in cfg.tweak_configs.iter() {
 				writeln!(
 					output,
-					"  {} is {}",
+					"  {} is {} (set \"{}\")",
 					tweak.as_str().yellow(),
 					if cfg.allow_or_deny.check(*state) {
 						"ENABLED".green()
 					} else {
 						"DISABLED".red()
-					}
+					},
+					if *state { "on" } else { "off" }
 				)?;
 			}
+			// Tweaks without an entry aren't listed, but they still load (or don't).
+			writeln!(
+				output,
+				"  {} {}",
+				"every other tweak has no entry, so it's".dark_gray(),
+				if cfg.allow_or_deny.check(false) {
+					"ENABLED".green()
+				} else {
+					"DISABLED".red()
+				}
+			)?;
 		}
 		println!("{}", output);
 	}
 
 	Ok(())
 }
+
+fn view_effective(
+	config: &LibhookerConfig,
+	providers: &Providers,
+	targets: Vec<Target>,
+) -> Result<()> {
+	let installed_tweaks = providers.tweaks.tweaks()?;
+	let apps = providers.apps.as_ref();
+	if !output::is_text() {
+		let records = targets
+			.into_iter()
+			.map(|target| EffectiveRecord {
+				name: target.display(apps).to_string(),
+				effective: effective_config(&config.tweak_configs, &target, installed_tweaks),
+				target,
+			})
+			.collect::<Vec<EffectiveRecord>>();
+		return output::print_records(&records);
+	}
+
+	for target in targets {
+		let effective = effective_config(&config.tweak_configs, &target, installed_tweaks);
+		let mut output = format!(
+			"effective configuration for {}\n",
+			target.display(apps).to_string().yellow()
+		);
+		writeln!(
+			output,
+			" {} is {}",
+			"tweak loading".blue(),
+			if effective.enable_tweaks {
+				"on".green()
+			} else {
+				"off".red()
+			}
+		)?;
+		writeln!(
+			output,
+			" using {}, in {} mode",
+			match effective.scope {
+				Scope::Target => "it's own configuration",
+				Scope::Default => "the default configuration",
+			},
+			effective.mode
+		)?;
+		writeln!(output, " tweaks:")?;
+		for tweak in &effective.tweaks {
+			let setting = match tweak.value {
+				Some(true) => "set \"on\"",
+				Some(false) => "set \"off\"",
+				None => "no entry",
+			};
+			writeln!(
+				output,
+				"  {} is {} ({})",
+				tweak
+					.tweak
+					.strip_suffix(".dylib")
+					.unwrap_or(&tweak.tweak)
+					.yellow(),
+				if tweak.loads {
+					"ENABLED".green()
+				} else {
+					"DISABLED".red()
+				},
+				match tweak.origin {
+					Origin::Explicit => format!("{} {}", setting, "here".cyan()),
+					Origin::Inherited => format!(
+						"{}, {}",
+						setting,
+						"inherited from the default configuration".magenta()
+					),
+					Origin::Unset => setting.dark_gray().to_string(),
+				}
+			)?;
+		}
+		println!("{}", output);
+	}
+	Ok(())
+}
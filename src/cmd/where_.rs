@@ -0,0 +1,107 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::{
+	cmd::output::{self, Record},
+	providers::Providers,
+};
+use bender_core::{
+	index::{TweakEntry, TweakIndex},
+	libhooker::LibhookerConfig,
+	tweaks::fix_tweak_name,
+};
+use color_eyre::eyre::Result;
+use colorful::Colorful;
+use serde::Serialize;
+
+/// A tweak's entry in one target, for machine-readable output.
+#[derive(Serialize)]
+struct EntryRecord<'a> {
+	/// The name of the target, as it's shown to humans.
+	name: String,
+	#[serde(flatten)]
+	entry: &'a TweakEntry,
+}
+
+impl Record for EntryRecord<'_> {
+	fn tsv(&self) -> Vec<String> {
+		vec![
+			self.entry.target.key(),
+			self.name.clone(),
+			self.entry.value.to_string(),
+			self.entry.mode.name().to_string(),
+			self.entry.allows.to_string(),
+			self.entry.ignored.to_string(),
+			self.entry.loads.to_string(),
+		]
+	}
+}
+
+pub fn where_(config: LibhookerConfig, providers: &Providers, tweak: &str) -> Result<()> {
+	let apps = providers.apps.as_ref();
+	let index = TweakIndex::build(&config.tweak_configs);
+	// A tweak that's been uninstalled can still be configured, and that's exactly
+	// when it's worth finding, so fall back to the tweaks in the configuration.
+	let configured_tweaks = index.tweaks().cloned().collect::<Vec<String>>();
+	let tweak = match fix_tweak_name(providers.tweaks.as_ref(), tweak)? {
+		Some(tweak) => tweak,
+		None => match fix_tweak_name(&configured_tweaks, tweak)? {
+			Some(tweak) => tweak,
+			None => super::resolve_tweak(tweak, providers)?,
+		},
+	};
+	let entries = index.get(&tweak);
+	if !output::is_text() {
+		let records = entries
+			.iter()
+			.map(|entry| EntryRecord {
+				name: entry.target.display(apps).to_string(),
+				entry,
+			})
+			.collect::<Vec<EntryRecord>>();
+		return output::print_records(&records);
+	}
+
+	let readable_tweak_name = tweak.strip_suffix(".dylib").unwrap_or(&tweak);
+	if entries.is_empty() {
+		println!(
+			"{} isn't configured anywhere, so every target just uses it's mode!",
+			readable_tweak_name.yellow()
+		);
+		return Ok(());
+	}
+	println!("{} is configured for:", readable_tweak_name.yellow());
+	for entry in entries {
+		println!(
+			" {}: {} (set \"{}\" in {} mode){}, so it {}",
+			entry.target.display(apps).to_string().yellow(),
+			if entry.allows {
+				"allowed".green()
+			} else {
+				"denied".red()
+			},
+			if entry.value { "on" } else { "off" },
+			entry.mode,
+			if entry.ignored {
+				format!(
+					", {}",
+					"but ignored, since custom configuration is off".dark_gray()
+				)
+			} else {
+				String::new()
+			},
+			if entry.loads {
+				"WILL LOAD".light_green()
+			} else {
+				"WILL NOT LOAD".red()
+			}
+		);
+	}
+	Ok(())
+}
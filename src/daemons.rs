@@ -7,8 +7,14 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
+use bender_core::{
+	daemons::{is_ignored_label, Daemon, ServiceCatalog},
+	paths,
+};
+use color_eyre::eyre::{bail, eyre, Result, WrapErr};
+use colorful::Colorful;
 use libc::{c_char, close, fcntl, pipe, read, F_SETFL, O_NONBLOCK};
-use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
 use std::{collections::HashMap, ffi::CStr, path::PathBuf, process::Command};
 use xpc_connection::{message_to_xpc_object, xpc_object_to_message, Message as XpcMessage};
 use xpc_connection_sys::{_os_alloc_once_table, xpc_global_data, xpc_object_t};
@@ -27,14 +33,16 @@ fn xpc_bootstrap_pipe() -> xpc_object_t {
 }
 
 /// Get a service's full path to it's executable from it's label.
-pub fn lookup_service(name: &str) -> Option<PathBuf> {
+/// Returns `None` if launchd doesn't know of an executable for it.
+pub fn lookup_service(name: &str) -> Result<Option<PathBuf>> {
 	const ROUTINE_DUMP_PROCESS: XpcMessage = XpcMessage::Uint64(0x2c4);
 	const PROGRAM_PREFIX: &str = "program = ";
 
 	// Create an IPC pipe.
 	let mut fds: [i32; 2] = [0, 0];
 	if unsafe { pipe(fds.as_mut_ptr()) } != 0 {
-		return None;
+		return Err(std::io::Error::last_os_error())
+			.wrap_err_with(|| format!("failed to create a pipe to look up {}", name));
 	}
 	unsafe { fcntl(fds[0], F_SETFL, O_NONBLOCK) };
 
@@ -61,30 +69,29 @@ pub fn lookup_service(name: &str) -> Option<PathBuf> {
 			&mut out_dict as *mut _,
 		)
 	};
-	// Convert the output to a dictionary. If it's not a dictionary, then something is wrong
-	// and we should just panic.
+	// Convert the output to a dictionary. If it's not a dictionary, then something is wrong,
+	// and there's no point in going any further.
 	let out_dict = match xpc_object_to_message(out_dict) {
 		XpcMessage::Dictionary(d) => d,
-		_ => panic!("failed to get xpc dict",),
+		_ => {
+			unsafe { close(fds[0]) };
+			unsafe { close(fds[1]) };
+			bail!(xpc_failure(name, "launchd didn't reply with a dictionary"));
+		}
 	};
 	// If our `out_dict` isn't empty, that probably means there's an error value in it.
 	if !out_dict.is_empty() {
-		// Extract the error value, a 64-bit signed integer, from the dictionary
-		let err = match out_dict
-			.get("error")
-			.expect("failed to check for xpc error")
-		{
-			XpcMessage::Int64(i) => *i,
-			_ => panic!("xpc error was not int64 even though it should be"),
+		unsafe { close(fds[0]) };
+		unsafe { close(fds[1]) };
+		// Extract the error value, a 64-bit signed integer, from the dictionary,
+		// and convert the error code to a human-readable string.
+		let err = match out_dict.get("error") {
+			Some(XpcMessage::Int64(i)) => unsafe { CStr::from_ptr(xpc_strerror(*i as i32)) }
+				.to_string_lossy()
+				.into_owned(),
+			_ => "launchd replied with something other than an error code".to_string(),
 		};
-		// Now, we panic, after converting the error code to a human-readable string.
-		panic!(
-			"xpc error: {}",
-			unsafe { CStr::from_ptr(xpc_strerror(err as i32)) }
-				.to_str()
-				.unwrap()
-				.to_owned()
-		);
+		bail!(xpc_failure(name, &err));
 	}
 	// Close the output pipe that we gave to xpc, we're done with it for now.
 	unsafe { close(fds[1]) };
@@ -107,8 +114,9 @@ pub fn lookup_service(name: &str) -> Option<PathBuf> {
 	unsafe { close(fds[0]) };
 
 	// Convert the output to a UTF-8 String, it's some weird plist-like format.
-	let output = String::from_utf8(output).expect("xpc did not return valid utf-8 string");
-	output
+	let output = String::from_utf8(output)
+		.map_err(|_| eyre!(xpc_failure(name, "launchd gave invalid utf8")))?;
+	Ok(output
 		// Now, we split up our output by lines
 		.lines()
 		// Our goal is to find something like "program = /usr/bin/stupidd".
@@ -118,24 +126,44 @@ pub fn lookup_service(name: &str) -> Option<PathBuf> {
 		.find_map(|line| line.trim().strip_prefix(PROGRAM_PREFIX))
 		// Convert the string to a PathBuf
 		.map(PathBuf::from)
-		// And ensure that this path is, in fact, a valid, existing file.
-		.filter(|path| path.is_file())
+		// And ensure that this path is, in fact, a valid, existing file, under the root we're using.
+		.filter(|path| paths::resolve(path).is_file()))
+}
+
+/// Explain what went wrong talking to launchd, and what to do instead.
+fn xpc_failure(name: &str, err: &str) -> String {
+	format!(
+		"failed to look up {} with launchd: {}\nUse `--daemons-from plists` to read the daemon plists instead.",
+		name, err
+	)
+}
+
+/// Gets the daemons from the live system, using `launchctl list` and XPC.
+#[derive(Default)]
+pub struct LaunchctlServices {
+	daemons: OnceCell<Vec<Daemon>>,
 }
 
-// This is a "lazy static" global, it's initialized on first use,
-// then reused for subsequent uses.
-pub static DAEMONS: Lazy<Vec<PathBuf>> = Lazy::new(|| {
-	assert!(std::path::PathBuf::from("/.procursus_strapped").is_file());
+impl ServiceCatalog for LaunchctlServices {
+	fn daemons(&self) -> Result<&[Daemon]> {
+		// This is initialized on first use, then reused for subsequent uses.
+		self.daemons
+			.get_or_try_init(load_launchctl_daemons)
+			.map(Vec::as_slice)
+	}
+}
+
+fn load_launchctl_daemons() -> Result<Vec<Daemon>> {
 	// Here we call `launchctl list`, read stdout, and
 	// then try to convert it to a UTF-8 String.
 	let output = String::from_utf8(
 		Command::new("launchctl")
 			.arg("list")
 			.output()
-			.expect("failed to run `launchctl list`!")
+			.wrap_err("failed to run `launchctl list`!")?
 			.stdout,
 	)
-	.expect("launchctl gave invalid utf8... what?");
+	.wrap_err("launchctl gave invalid utf8... what?")?;
 
 	let mut out = output
 		// Split the output by newline
@@ -143,20 +171,29 @@ pub static DAEMONS: Lazy<Vec<PathBuf>> = Lazy::new(|| {
 		// Skip the first line of launchctl (it just says "PID STATUS LABEL")
 		.skip(1)
 		// Split each line by whitespace, and only keep the third entry, the label.
-		.map(|x| x.split_ascii_whitespace().collect::<Vec<&str>>()[2])
+		// If a line somehow doesn't have three entries, we just skip it.
+		.filter_map(|x| x.split_ascii_whitespace().nth(2))
 		// Don't include jailbreakd, amfidebilitate, or UIKit shit in the daemon list.
-		.filter(|name| {
-			*name != "jailbreakd"
-				&& *name != "amfidebilitate"
-				&& !name.starts_with("UIKitApplication:")
+		.filter(|label| !is_ignored_label(label))
+		// Now, we call `lookup_service` to get the full path of the daemon,
+		// skipping any that don't have one.
+		.filter_map(|label| match lookup_service(label) {
+			Ok(executable) => executable.map(|executable| Daemon {
+				label: label.to_string(),
+				executable,
+			}),
+			// One daemon launchd won't tell us about shouldn't hide all the others,
+			// so just point it out and move on.
+			Err(err) => {
+				eprintln!("{} {:#}", "warning:".yellow(), err);
+				None
+			}
 		})
-		// Now, we call `lookup_service` to get the full path of the daemon
-		.filter_map(|name| lookup_service(name))
 		// And finally, convert this all into one big vec.
-		.collect::<Vec<PathBuf>>();
+		.collect::<Vec<Daemon>>();
 
-	// Pre-sort the paths.
-	out.sort();
+	// Pre-sort the daemons.
+	out.sort_by(|a, b| a.executable.cmp(&b.executable));
 
-	out
-});
+	Ok(out)
+}
@@ -7,47 +7,87 @@
 	🏳️‍🌈🏳️‍⚧️
 */
 
+#[cfg(target_os = "ios")]
 #[macro_use]
 extern crate objc;
 
+#[cfg(target_os = "ios")]
 pub mod apps;
 pub mod cmd;
+#[cfg(target_os = "ios")]
 pub mod daemons;
-pub mod libhooker;
-pub mod tweaks;
+pub mod providers;
 
 use crate::{
-	cmd::CmdOpts,
-	libhooker::{LibhookerConfig, Target, TweakMode},
+	cmd::{output, Cmd, CmdOpts},
+	providers::Providers,
+};
+use bender_core::{
+	doctor, gc,
+	history::History,
+	libhooker::{self, TweakMode},
+	manifest::Manifest,
+	paths,
+	store::ConfigFile,
+	tweaks,
 };
 use clap::Clap;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{bail, Result};
 use colorful::Colorful;
 use std::collections::BTreeMap;
 
-pub use crate::{apps::APPS, daemons::DAEMONS, tweaks::TWEAKS};
-
 fn main() -> Result<()> {
 	color_eyre::install()?;
 
-	assert!(std::path::PathBuf::from("/.procursus_strapped").is_file());
-
 	let opts = CmdOpts::parse();
-	let libhooker_config: LibhookerConfig =
-		plist::from_file("/var/mobile/Library/Preferences/org.coolstar.libhooker.plist")
-			.unwrap_or_default();
-	match opts {
-		CmdOpts::List(opt) => cmd::list::list(opt.cmd, libhooker_config)?,
-		CmdOpts::Config {
+	paths::set_root(opts.root);
+	// Only `bender export` knows how to write a whole configuration as YAML, TOML, or a plist.
+	if !matches!(opts.cmd, Cmd::Export) {
+		output::require_supported(opts.format)?;
+	}
+	output::set_format(opts.format);
+	// The doctor has to work even when nothing else does.
+	if let Cmd::Doctor = opts.cmd {
+		return cmd::doctor::doctor();
+	}
+	let providers = match opts.manifest {
+		Some(manifest) => Providers::from_manifest(Manifest::from_file(manifest)?),
+		None => {
+			doctor::bootstrap().require()?;
+			doctor::tweak_inject().require()?;
+			Providers::live(opts.apps_from, opts.daemons_from)
+		}
+	};
+
+	let history = History::new(paths::history_dir());
+	let mut config_file = ConfigFile::new(paths::libhooker_plist()).with_history(history.clone());
+	// Rolling back has to work even when the plist is broken, since that's usually why it's being rolled back.
+	match &opts.cmd {
+		Cmd::History => return cmd::history::history(&history),
+		Cmd::Restore { id } => {
+			return cmd::history::restore(&history, &mut config_file, &providers, opts.save, id)
+		}
+		Cmd::Undo => return cmd::history::undo(&history, &mut config_file, &providers, opts.save),
+		_ => {}
+	}
+	doctor::plist().require()?;
+	let mut libhooker_config = config_file.load()?;
+	// Keep the configuration as it was, so we can show what's changed before saving.
+	let original_config = libhooker_config.clone();
+	match opts.cmd {
+		Cmd::List(opt) => cmd::list::list(opt.cmd, libhooker_config, &providers)?,
+		Cmd::Config {
 			allow,
 			deny,
+			invert,
 			custom_config,
 			enable_tweaks,
 			add,
 			remove,
+			unset,
 			target,
 		} => {
-			let target = Target::from(target);
+			let target = cmd::resolve_target(&target, &providers)?;
 			let mode = if allow {
 				TweakMode::Allow
 			} else if deny {
@@ -55,63 +95,205 @@ fn main() -> Result<()> {
 			} else {
 				target.get_tweak_mode(&libhooker_config.tweak_configs)
 			};
-			println!(
-				"Configuring {} in {} mode",
-				target.to_string().yellow(),
-				mode
-			);
+			if output::is_text() {
+				println!(
+					"Configuring {} in {} mode",
+					target.display(providers.apps.as_ref()).to_string().yellow(),
+					mode
+				);
+			}
 
+			let installed_tweaks = providers.tweaks.tweaks()?;
 			let add = if add.contains(&"all".into()) {
-				TWEAKS.iter()
+				installed_tweaks.iter()
 			} else {
 				add.iter()
 			};
 
 			let remove = if remove.contains(&"all".into()) {
-				TWEAKS.iter()
+				installed_tweaks.iter()
 			} else {
 				remove.iter()
 			};
 
-			let changes: BTreeMap<String, bool> = add
+			// Tweaks can be unset even after they've been uninstalled,
+			// so look at the tweaks the target has entries for first.
+			let entries = target
+				.find_config(&libhooker_config.tweak_configs)
+				.map(|tweak_config| tweak_config.tweak_configs.keys().cloned().collect())
+				.unwrap_or_else(Vec::new);
+			let unset = if unset.contains(&"all".into()) {
+				entries.clone()
+			} else {
+				unset
+					.iter()
+					.map(|tweak| match tweaks::fix_tweak_name(&entries, tweak)? {
+						Some(tweak) => Ok(tweak),
+						None => cmd::resolve_tweak(tweak, &providers),
+					})
+					.collect::<Result<_>>()?
+			};
+
+			let mut changes: BTreeMap<String, Option<bool>> = add
 				.zip(std::iter::repeat(true))
 				.chain(remove.zip(std::iter::repeat(false)))
 				.map(|(tweak, config)| {
-					let tweak = tweaks::fix_tweak_name(&tweak).unwrap_or_else(|| {
-						eprintln!(
-							"Tweak '{}' not found!\nUse `{}` to see a list of available tweaks!",
-							tweak.clone().red(),
-							"bender list tweaks".green()
+					let tweak = cmd::resolve_tweak(tweak, &providers)?;
+					if output::is_text() {
+						println!(
+							"{} {}",
+							if mode.check(config) {
+								"ALLOWING".green()
+							} else {
+								"DENYING".red()
+							},
+							tweak.strip_suffix(".dylib").unwrap_or(&tweak)
 						);
-						std::process::exit(1);
-					});
+					}
+					Ok((tweak, Some(config)))
+				})
+				.collect::<Result<_>>()?;
+			// Unsetting a tweak that's also being added or removed would quietly undo that,
+			// so make people pick one.
+			if let Some(tweak) = unset.iter().find(|tweak| changes.contains_key(*tweak)) {
+				bail!(
+					"{} is in --unset, and in --add or --remove too, pick one!",
+					tweak.strip_suffix(".dylib").unwrap_or(tweak)
+				);
+			}
+			for tweak in unset {
+				if output::is_text() {
 					println!(
 						"{} {}",
-						if mode.check(config) {
-							"ALLOWING".green()
-						} else {
-							"DENYING".red()
-						},
+						"UNSETTING".dark_gray(),
 						tweak.strip_suffix(".dylib").unwrap_or(&tweak)
 					);
-					(tweak, config)
-				})
-				.collect();
+				}
+				changes.insert(tweak, None);
+			}
 
 			cmd::config::configure(
-				libhooker_config,
+				&mut libhooker_config,
 				target,
 				custom_config,
 				enable_tweaks,
 				mode,
+				invert,
+				installed_tweaks,
 				changes,
 			)?;
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
 		}
-		CmdOpts::View { target } => {
-			let target = target.map(Target::from);
-			cmd::view::view(libhooker_config, target)?;
+		Cmd::View { target, effective } => {
+			let target = target
+				.map(|target| cmd::resolve_target(&target, &providers))
+				.transpose()?;
+			cmd::view::view(libhooker_config, &providers, target, effective)?;
 		}
-		CmdOpts::Compat {
+		Cmd::Explain { target, tweaks } => {
+			let target = cmd::resolve_target(&target, &providers)?;
+			let tweaks = tweaks
+				.iter()
+				.map(|tweak| cmd::resolve_tweak(tweak, &providers))
+				.collect::<Result<_>>()?;
+			cmd::explain::explain(libhooker_config, &providers, target, tweaks)?;
+		}
+		Cmd::Where { tweak } => cmd::where_::where_(libhooker_config, &providers, &tweak)?,
+		Cmd::Apply { file, check } => cmd::apply::apply(
+			&mut config_file,
+			&libhooker_config,
+			&providers,
+			opts.save,
+			&file,
+			check,
+		)?,
+		Cmd::Diff { old, new } => {
+			cmd::diff::diff(libhooker_config, &providers, &history, &old, &new)?
+		}
+		Cmd::Export => cmd::export::export(&libhooker_config)?,
+		Cmd::Import { file, force } => cmd::export::import(
+			&mut config_file,
+			&libhooker_config,
+			&providers,
+			opts.save,
+			&file,
+			force,
+		)?,
+		Cmd::Profile(opt) => cmd::profile::profile(
+			opt.cmd,
+			&mut config_file,
+			&libhooker_config,
+			&providers,
+			opts.save,
+		)?,
+		// These were handled before the configuration was read.
+		Cmd::History | Cmd::Restore { .. } | Cmd::Undo => {}
+		Cmd::Lint { fix } => cmd::lint::lint(
+			&mut config_file,
+			&libhooker_config,
+			&providers,
+			opts.save,
+			fix,
+		)?,
+		// clap makes sure there's either a target or --all, so no target means --all.
+		Cmd::Reset { target, .. } => {
+			let target = target
+				.map(|target| cmd::resolve_target(&target, &providers))
+				.transpose()?;
+			cmd::reset::reset(&mut libhooker_config, &providers, target)?;
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
+		}
+		Cmd::Clone {
+			source,
+			destinations,
+		} => {
+			let source = cmd::resolve_target(&source, &providers)?;
+			let destinations = destinations
+				.iter()
+				.map(|target| cmd::resolve_target(target, &providers))
+				.collect::<Result<_>>()?;
+			cmd::clone::clone(&mut libhooker_config, &providers, source, destinations)?;
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
+		}
+		Cmd::Gc => {
+			gc::compact(&mut libhooker_config);
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
+		}
+		Cmd::Tweak(opt) => {
+			cmd::tweak::tweak(opt.cmd, &mut libhooker_config, &providers)?;
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
+		}
+		Cmd::Compat {
 			libhooker,
 			substrate,
 		} => {
@@ -124,28 +306,32 @@ fn main() -> Result<()> {
 						.zip(std::iter::repeat(libhooker::COMPAT_SUBSTRATE)),
 				)
 				.map(|(tweak, compat_mode)| {
-					let tweak = tweaks::fix_tweak_name(&tweak).unwrap_or_else(|| {
-						eprintln!(
-							"Tweak '{}' not found!\nUse `{}` to see a list of available tweaks!",
-							tweak.clone().red(),
-							"bender list tweaks".green()
+					let tweak = cmd::resolve_tweak(&tweak, &providers)?;
+					if output::is_text() {
+						println!(
+							"Setting {} to {} mode",
+							tweak.strip_suffix(".dylib").unwrap_or(&tweak),
+							if compat_mode == libhooker::COMPAT_LIBHOOKER {
+								"libhooker default".blue()
+							} else {
+								"substrate compatibility".magenta()
+							},
 						);
-						std::process::exit(1);
-					});
-					println!(
-						"Setting {} to {} mode",
-						tweak.strip_suffix(".dylib").unwrap_or(&tweak),
-						if compat_mode == libhooker::COMPAT_LIBHOOKER {
-							"libhooker default".blue()
-						} else {
-							"substrate compatibility".magenta()
-						},
-					);
-					(tweak, compat_mode)
+					}
+					Ok((tweak, compat_mode))
 				})
-				.collect();
-			cmd::compat::compat(libhooker_config, changes)?;
+				.collect::<Result<_>>()?;
+			cmd::compat::compat(&mut libhooker_config, changes);
+			cmd::save(
+				&mut config_file,
+				&original_config,
+				&libhooker_config,
+				&providers,
+				opts.save,
+			)?;
 		}
+		// This was handled before anything was loaded.
+		Cmd::Doctor => {}
 	}
 	Ok(())
 }
@@ -0,0 +1,97 @@
+/*
+	bender - a CLI interface for configuring libhooker on iOS
+	copyright (C) 2021, aspen <aspenuwu@protonmail.com>
+	All rights reserved.
+
+	LGBTQ+ rights are human rights. If you disagree, kindly fuck off and don't use my code :)
+	🏳️‍🌈🏳️‍⚧️
+*/
+
+use crate::cmd::{AppSource, DaemonSource};
+use bender_core::{
+	apps::{AppCatalog, BundleApps},
+	daemons::{LaunchDaemonPlists, ServiceCatalog},
+	manifest::Manifest,
+	paths,
+	tweaks::{TweakInjectDir, TweakSource},
+};
+use std::path::Path;
+
+/// Everything bender needs to know about the device it's configuring:
+/// which tweaks are installed, which apps are installed, and which daemons exist.
+pub struct Providers {
+	pub tweaks: Box<dyn TweakSource>,
+	pub apps: Box<dyn AppCatalog>,
+	pub services: Box<dyn ServiceCatalog>,
+}
+
+impl Providers {
+	/// Get everything from the live device, or from the filesystem under the root.
+	pub fn live(apps_from: Option<AppSource>, daemons_from: Option<DaemonSource>) -> Self {
+		Self {
+			tweaks: Box::new(TweakInjectDir::new(paths::tweak_inject())),
+			apps: live_apps(apps_from.unwrap_or_else(default_app_source)),
+			services: live_services(daemons_from.unwrap_or_else(default_daemon_source)),
+		}
+	}
+
+	/// Get everything from a manifest, rather than from the device.
+	pub fn from_manifest(manifest: Manifest) -> Self {
+		Self {
+			tweaks: Box::new(manifest.tweaks),
+			apps: Box::new(manifest.apps),
+			services: Box::new(manifest.daemons),
+		}
+	}
+}
+
+/// Whether we're looking at the device we're actually running on.
+fn is_live_device() -> bool {
+	cfg!(target_os = "ios") && paths::root() == Path::new("/")
+}
+
+/// Only a live device can be asked about it's apps, anywhere else we have to read the app bundles.
+fn default_app_source() -> AppSource {
+	if is_live_device() {
+		AppSource::Workspace
+	} else {
+		AppSource::Bundles
+	}
+}
+
+/// Only a live device can be asked about it's daemons, anywhere else we have to read the launchd plists.
+fn default_daemon_source() -> DaemonSource {
+	if is_live_device() {
+		DaemonSource::Launchctl
+	} else {
+		DaemonSource::Plists
+	}
+}
+
+#[cfg(target_os = "ios")]
+fn live_apps(source: AppSource) -> Box<dyn AppCatalog> {
+	match source {
+		AppSource::Workspace => Box::new(crate::apps::WorkspaceApps::default()),
+		AppSource::Bundles => Box::new(BundleApps::default()),
+	}
+}
+
+#[cfg(not(target_os = "ios"))]
+fn live_apps(_source: AppSource) -> Box<dyn AppCatalog> {
+	// LSApplicationWorkspace only exists on iOS.
+	Box::new(BundleApps::default())
+}
+
+#[cfg(target_os = "ios")]
+fn live_services(source: DaemonSource) -> Box<dyn ServiceCatalog> {
+	match source {
+		DaemonSource::Launchctl => Box::new(crate::daemons::LaunchctlServices::default()),
+		DaemonSource::Plists => Box::new(LaunchDaemonPlists::default()),
+	}
+}
+
+#[cfg(not(target_os = "ios"))]
+fn live_services(_source: DaemonSource) -> Box<dyn ServiceCatalog> {
+	// launchctl and XPC only work on iOS.
+	Box::new(LaunchDaemonPlists::default())
+}